@@ -111,10 +111,15 @@ impl Application {
                     self.graphics.player_choices[player_number] = Some(Default::default());
                     self.graphics.load_pregame();
                 }
+                ClientBoundPacket::AvailableTracks(tracks) => {
+                    self.graphics.available_tracks = tracks;
+                    self.graphics.maybe_update_track_vote();
+                }
                 ClientBoundPacket::PlayerJoined(player_number) => {
                     if player_number != self.graphics.player_num {
                         self.graphics.player_choices[player_number] = Some(Default::default());
                     }
+                    self.graphics.maybe_update_track_vote();
                 }
 
                 ClientBoundPacket::PlayerChairChoice(player_num, chair) => {
@@ -130,8 +135,9 @@ impl Application {
                     println!("Player #{} has voted for map {}!", player_num, map.clone());
                     self.graphics.player_choices[player_num]
                         .as_mut()
-                        .expect("Attempted to set chair on player we don't know about!")
+                        .expect("Attempted to set map on player we don't know about!")
                         .map = map;
+                    self.graphics.maybe_update_track_vote();
                 }
                 ClientBoundPacket::PlayerReadyStatus(player_num, status) => {
                     self.graphics.maybe_set_chair_readiness(player_num, status);
@@ -142,6 +148,12 @@ impl Application {
                     );
                 }
 
+                ClientBoundPacket::TrackLoadFailed { track, reason } => {
+                    println!("The server couldn't load {}: {}", track, reason);
+                    self.graphics.lobby_notice =
+                        Some(format!("{} is broken! Vote for another track", track));
+                    self.graphics.maybe_update_track_vote();
+                }
                 ClientBoundPacket::LoadGame(map) => {
                     println!("Loading map {}!", map);
                     self.graphics.load_map(map);
@@ -166,6 +178,20 @@ impl Application {
                             }
                         });
                 }
+                ClientBoundPacket::DriftUpdate(charges) => {
+                    self.graphics.drift_charges = charges;
+                    if let Some(&charge) = charges.get(self.graphics.player_num as usize) {
+                        self.graphics.update_drift_meter(charge);
+                    }
+                }
+                ClientBoundPacket::PlayerRespawned(player_num) => {
+                    if player_num == self.graphics.player_num {
+                        self.graphics.start_respawn_fade();
+                    }
+                }
+                ClientBoundPacket::WrongWay(wrong_way) => {
+                    self.graphics.set_wrong_way(wrong_way);
+                }
                 ClientBoundPacket::PlacementUpdate(given_position) => {
                     let position = if given_position > 5 {
                         4
@@ -175,6 +201,21 @@ impl Application {
 
                     self.graphics.maybe_update_place(position);
                 }
+                ClientBoundPacket::LapCompleted {
+                    lap_time,
+                    best_lap,
+                    delta_to_best,
+                } => {
+                    println!(
+                        "Lap {} took {:?} (splits {:?})",
+                        lap_time.lap, lap_time.time, lap_time.splits
+                    );
+                    self.graphics
+                        .show_lap_time(&lap_time, best_lap, delta_to_best);
+                }
+                ClientBoundPacket::GapUpdate(gap) => {
+                    self.graphics.update_gap(gap);
+                }
                 ClientBoundPacket::LapUpdate(lap_num) => {
                     println!("I am now on lap {}!", lap_num);
                     self.graphics.maybe_update_lap(lap_num);
@@ -184,6 +225,13 @@ impl Application {
                         SourceOptions::new(),
                     );
                 }
+                ClientBoundPacket::TrackLoaded(metadata) => {
+                    println!(
+                        "Racing {} laps on {}!",
+                        metadata.laps, metadata.display_name
+                    );
+                    self.graphics.track_metadata = Some(metadata);
+                }
                 ClientBoundPacket::GameStart(duration) => {
                     self.graphics.display_hud();
                     self.sfx_manager.play(
@@ -193,7 +241,36 @@ impl Application {
                     );
                     self.game_start_time = SystemTime::now() + duration;
                 }
-                ClientBoundPacket::PowerupPickup => println!("we got a powerup!"),
+                ClientBoundPacket::WorldEntitySpawned { id, kind, location } => {
+                    self.graphics.spawn_world_entity(id, kind, &location);
+                }
+                ClientBoundPacket::WorldEntityUpdate(locations) => {
+                    for (id, location) in locations {
+                        self.graphics.update_world_entity(id, &location);
+                    }
+                }
+                ClientBoundPacket::WorldEntityDespawned(id) => {
+                    self.graphics.despawn_world_entity(id);
+                }
+                ClientBoundPacket::ItemBoxesPlaced(positions) => {
+                    self.graphics.place_item_boxes(positions);
+                }
+                ClientBoundPacket::ItemBoxAvailability(idx, available) => {
+                    self.graphics.set_item_box_available(idx, available);
+                }
+                ClientBoundPacket::PowerupPickup(powerup) => {
+                    self.graphics.display_held_powerup(Some(powerup));
+                }
+                ClientBoundPacket::PowerupActivated {
+                    user,
+                    powerup,
+                    targets,
+                } => {
+                    if user == self.graphics.player_num {
+                        self.graphics.display_held_powerup(None);
+                    }
+                    self.graphics.show_powerup_activated(user, powerup, targets);
+                }
                 ClientBoundPacket::VotingStarted {
                     time_until_vote_end,
                     question,
@@ -216,12 +293,14 @@ impl Application {
                     decision,
                     time_effect_is_live,
                     winner_idx,
+                    targets,
                 } => {
                     self.graphics.start_audience_interaction(
                         question,
                         decision,
                         time_effect_is_live,
                         winner_idx,
+                        targets,
                     );
 
                     self.sfx_manager.play(
@@ -237,7 +316,10 @@ impl Application {
                         SourceOptions::new(),
                     );
                 }
-                ClientBoundPacket::AllDone { placements } => {
+                ClientBoundPacket::AllDone {
+                    placements,
+                    lap_times,
+                } => {
                     let mut placement_info: [Placement; 4] = [4; 4];
                     let mut times: [(u64, u32); 4] = [(0, 0); 4];
                     self.sfx_manager.play(
@@ -258,8 +340,14 @@ impl Application {
                             .collect::<String>()
                     );
 
-                    self.graphics
-                        .display_final_standings(placement_info, self.chairs, times);
+                    let best_laps =
+                        lap_times.map(|laps| laps.iter().map(|lap_time| lap_time.time).min());
+                    self.graphics.display_final_standings(
+                        placement_info,
+                        self.chairs,
+                        times,
+                        best_laps,
+                    );
                 }
                 ClientBoundPacket::StartNextGame => {
                     self.game.pick_chair(
@@ -277,6 +365,9 @@ impl Application {
                     );
                 }
                 ClientBoundPacket::VotingCooldown => (),
+                ClientBoundPacket::AudienceEmotes(emotes) => {
+                    self.graphics.show_audience_emotes(emotes);
+                }
                 ClientBoundPacket::FinishedLaps(placement) => {
                     self.graphics.display_finished_text(placement);
                 }
@@ -1,3 +1,33 @@
+use chariot_core::networking::ws::Emote;
+use chariot_core::powerup::PowerUp;
+
 // fire particles
 pub const FIRE: &[u8] = include_bytes!("fire.png");
 pub const SMOKE: &[u8] = include_bytes!("smoke.png");
+
+// held powerup icons
+const COFFEE: &[u8] = include_bytes!("powerUpArt.png");
+pub const SHOCK_EM: &[u8] = include_bytes!("Shockem.png");
+const WET_FLOOR_SIGN: &[u8] = include_bytes!("wetfloorsign.png");
+
+pub fn get_powerup_icon(powerup: PowerUp) -> &'static [u8] {
+    match powerup {
+        PowerUp::Coffee => COFFEE,
+        PowerUp::ShockEm => SHOCK_EM,
+        PowerUp::WetFloorSign => WET_FLOOR_SIGN,
+    }
+}
+
+// audience emotes
+const CHEER: &[u8] = include_bytes!("cheer.png");
+const BOO: &[u8] = include_bytes!("boo.png");
+const LAUGH: &[u8] = include_bytes!("laugh.png");
+
+pub fn get_emote_icon(emote: Emote) -> &'static [u8] {
+    match emote {
+        Emote::Cheer => CHEER,
+        Emote::Boo => BOO,
+        Emote::Laugh => LAUGH,
+        Emote::Fire => FIRE,
+    }
+}
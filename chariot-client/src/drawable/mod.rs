@@ -197,7 +197,7 @@ impl AnimatedUIDrawable {
         }
     }
 
-    pub fn _col_to(&mut self, index: usize, end_color: [f32; 4], duration: Duration) {
+    pub fn col_to(&mut self, index: usize, end_color: [f32; 4], duration: Duration) {
         if let Some((ui, _, _, color_animation)) = self.layers.get_mut(index) {
             *color_animation = Some(UIAnimation::ColorAnimation {
                 start_color: ui.tint_color,
@@ -48,7 +48,7 @@ impl GameClient {
         self.send_packet(ServerBoundPacket::ChairSelect(chair));
     }
 
-    pub fn _pick_map(&mut self, map: Track) {
+    pub fn pick_map(&mut self, map: Track) {
         self.send_packet(ServerBoundPacket::MapSelect(map));
     }
 
@@ -68,6 +68,14 @@ impl GameClient {
         self.send_packet(ServerBoundPacket::InputToggle(event));
     }
 
+    pub fn use_powerup(&mut self) {
+        self.send_packet(ServerBoundPacket::UsePowerup);
+    }
+
+    pub fn request_respawn(&mut self) {
+        self.send_packet(ServerBoundPacket::RequestRespawn);
+    }
+
     pub fn next_game(&mut self) {
         self.send_packet(ServerBoundPacket::NextGame);
     }
@@ -9,10 +9,15 @@ use chariot_core::entity_location::EntityLocation;
 use chariot_core::player::choices::Chair;
 use chariot_core::player::choices::PlayerChoices;
 use chariot_core::player::choices::Track;
+use chariot_core::player::choices::TrackInfo;
+use chariot_core::player::choices::TrackMetadata;
 use chariot_core::player::PlayerID;
+use chariot_core::questions::AudienceTarget;
+use chariot_core::world_entity::{WorldEntityID, WorldEntityKind};
 use chariot_core::GLOBAL_CONFIG;
 use glam::{DVec3, Vec2};
 use image::ImageFormat;
+use std::collections::HashMap;
 use std::f64::consts::PI;
 
 use crate::drawable::particle::ParticleDrawable;
@@ -38,7 +43,7 @@ use crate::resources::*;
 use crate::scenegraph::components::*;
 use crate::scenegraph::particle_system::*;
 use crate::scenegraph::*;
-use crate::ui_state::UIState;
+use crate::ui_state::{InteractionState, UIState};
 
 pub fn register_passes(renderer: &mut Renderer) {
     StaticMeshDrawable::register(renderer);
@@ -97,6 +102,7 @@ fn setup_void() -> World {
 
     ParticleSystem::<0>::register_components(&mut world);
     ParticleSystem::<1>::register_components(&mut world);
+    ParticleSystem::<2>::register_components(&mut world);
 
     let world_root = world.root();
 
@@ -123,7 +129,14 @@ pub struct GraphicsManager {
     pub ui: UIState,
     pub player_num: PlayerID,
     pub player_choices: [Option<PlayerChoices>; 4],
+    pub available_tracks: Vec<TrackInfo>,
+    pub track_metadata: Option<TrackMetadata>,
+    pub lobby_notice: Option<String>, // shown in place of our track vote until we vote again
     pub player_entities: [Option<Entity>; 4],
+    pub drift_charges: [Option<f32>; 4], // None for anyone not drifting
+    world_entities: HashMap<WorldEntityID, (Entity, WorldEntityKind)>,
+    spare_world_entities: Vec<(Entity, WorldEntityKind)>, // hidden, waiting to be spawned again
+    item_boxes: Vec<Entity>,
     vsm_blur_horiz: VSMBlurHorizTechnique,
     vsm_blur_vert: VSMBlurVertTechnique,
     shade_direct: ShadeDirectTechnique,
@@ -139,6 +152,7 @@ pub struct GraphicsManager {
     simple_fsq: SimpleFSQTechnique,
     fire_particle_system: ParticleSystem<0>,
     smoke_particle_system: ParticleSystem<1>,
+    shock_particle_system: ParticleSystem<2>,
     prev_view: glam::Mat4,
     prev_proj: glam::Mat4,
     iteration: u32,
@@ -359,6 +373,28 @@ impl GraphicsManager {
                 gravity: -0.2,
             },
         );
+
+        let shock_handle = resources.import_texture_embedded(
+            &renderer,
+            "sprites/shockem",
+            assets::sprites::SHOCK_EM,
+            ImageFormat::Png,
+        );
+        let shock_particle_system = ParticleSystem::new(
+            &renderer,
+            &mut resources,
+            ParticleSystemParams {
+                texture_handle: shock_handle,
+                mesh_handle: quad_handle,
+                pos_range: (-glam::Vec3::ONE * 0.15, glam::Vec3::ONE * 0.15),
+                size_range: (Vec2::ONE * 2.0, Vec2::ONE * 4.0),
+                initial_vel: glam::Vec3::ZERO,
+                spawn_rate: 40.0,
+                lifetime: 0.3,
+                rotation: ParticleRotation::Billboard,
+                gravity: 0.0,
+            },
+        );
         let world = setup_void();
         let vsm_blur_horiz = VSMBlurHorizTechnique::new(&renderer, &resources, quad_handle, 1);
         let vsm_blur_vert = VSMBlurVertTechnique::new(&renderer, &resources, quad_handle, 1);
@@ -388,6 +424,9 @@ impl GraphicsManager {
             renderer,
             resources,
             player_choices: Default::default(),
+            available_tracks: vec![],
+            track_metadata: None,
+            lobby_notice: None,
             prev_view: glam::Mat4::IDENTITY,
             prev_proj: glam::Mat4::IDENTITY,
             iteration: 0,
@@ -405,10 +444,15 @@ impl GraphicsManager {
             composite_bloom,
             simple_fsq,
             player_entities: [None, None, None, None],
+            drift_charges: [None; 4],
+            world_entities: HashMap::new(),
+            spare_world_entities: vec![],
+            item_boxes: vec![],
             ui: UIState::None,
             player_num: 4,
             fire_particle_system,
             smoke_particle_system,
+            shock_particle_system,
             camera_entity: NULL_ENTITY,
             test_ui: AnimatedUIDrawable::new(),
             white_box_tex,
@@ -441,6 +485,7 @@ impl GraphicsManager {
 
         ParticleSystem::<0>::register_components(&mut world);
         ParticleSystem::<1>::register_components(&mut world);
+        ParticleSystem::<2>::register_components(&mut world);
 
         let world_root = world.root();
 
@@ -480,8 +525,19 @@ impl GraphicsManager {
 
     pub fn load_map(&mut self, map: Track) {
         self.world = self.setup_world(map);
+        self.world_entities.clear();
+        self.spare_world_entities.clear();
+        self.item_boxes.clear();
+        self.track_metadata = None;
 
         [0, 1, 2, 3].map(|player_num| self.add_player(player_num));
+
+        // import every world entity's model up front, so the first wet floor
+        // sign of the race doesn't hitch while it loads
+        for kind in [WorldEntityKind::WetFloorSign, WorldEntityKind::ShockEmBolt] {
+            let entity = self.build_world_entity(kind);
+            self.spare_world_entities.push((entity, kind));
+        }
     }
 
     pub fn load_dev_mode(&mut self, map: Track) {
@@ -564,11 +620,205 @@ impl GraphicsManager {
         self.player_entities[player_num as usize] = Some(chair);
     }
 
+    const WORLD_ENTITY_SCALE: f32 = 0.2;
+
+    // reuses a despawned entity of the same kind if there is one, since the
+    // scenegraph can't unlink nodes and importing a model every time adds up
+    pub fn spawn_world_entity(
+        &mut self,
+        id: WorldEntityID,
+        kind: WorldEntityKind,
+        location: &EntityLocation,
+    ) {
+        let entity = match self
+            .spare_world_entities
+            .iter()
+            .position(|&(_, spare_kind)| spare_kind == kind)
+        {
+            Some(idx) => self.spare_world_entities.swap_remove(idx).0,
+            None => self.build_world_entity(kind),
+        };
+
+        if let Some(transform) = self.world.get_mut::<Transform>(entity) {
+            *transform = Transform::from_entity_location(
+                location,
+                glam::Vec3::ONE * Self::WORLD_ENTITY_SCALE,
+            );
+        }
+
+        self.world_entities.insert(id, (entity, kind));
+    }
+
+    // starts out shrunk away to nothing until it's spawned somewhere
+    fn build_world_entity(&mut self, kind: WorldEntityKind) -> Entity {
+        let world_root = self.world.root();
+        let transform = Transform {
+            translation: glam::Vec3::ZERO,
+            rotation: glam::Quat::IDENTITY,
+            scale: glam::Vec3::ZERO,
+        };
+
+        match models::get_world_entity_data(kind) {
+            Some(model) => {
+                let entity_import = self
+                    .resources
+                    .import_gltf_slice(&mut self.renderer, model)
+                    .expect("Failed to import world entity");
+
+                self.world
+                    .builder()
+                    .attach(world_root)
+                    .with(transform)
+                    .with(entity_import.drawables)
+                    .with(entity_import.bounds)
+                    .build()
+            }
+            None => self
+                .world
+                .builder()
+                .attach(world_root)
+                .with(transform)
+                .build(),
+        }
+    }
+
+    pub fn update_world_entity(&mut self, id: WorldEntityID, location: &EntityLocation) {
+        if let Some(&(entity, _)) = self.world_entities.get(&id) {
+            if let Some(transform) = self.world.get_mut::<Transform>(entity) {
+                *transform = Transform::from_entity_location(location, transform.scale);
+            }
+        }
+    }
+
+    // shrink it away and keep it around for the next one of its kind
+    pub fn despawn_world_entity(&mut self, id: WorldEntityID) {
+        if let Some((entity, kind)) = self.world_entities.remove(&id) {
+            if let Some(transform) = self.world.get_mut::<Transform>(entity) {
+                transform.scale = glam::Vec3::ZERO;
+            }
+            self.spare_world_entities.push((entity, kind));
+        }
+    }
+
+    const ITEM_BOX_SCALE: f32 = 0.2;
+    const ITEM_BOX_SPIN_SPEED: f32 = 2.0; // radians per second
+
+    pub fn place_item_boxes(&mut self, positions: Vec<DVec3>) {
+        let world_root = self.world.root();
+        for position in positions {
+            let item_box_import = self
+                .resources
+                .import_gltf_slice(&mut self.renderer, &models::POWERUP)
+                .expect("Failed to import item box");
+
+            let item_box = self
+                .world
+                .builder()
+                .attach(world_root)
+                .with(Transform {
+                    translation: position.as_vec3(),
+                    rotation: glam::Quat::IDENTITY,
+                    scale: glam::Vec3::ONE * Self::ITEM_BOX_SCALE,
+                })
+                .with(item_box_import.drawables)
+                .with(item_box_import.bounds)
+                .build();
+
+            self.item_boxes.push(item_box);
+        }
+    }
+
+    // taken boxes shrink away until they respawn, with a puff of smoke when
+    // someone grabs one
+    pub fn set_item_box_available(&mut self, idx: usize, available: bool) {
+        if let Some(&item_box) = self.item_boxes.get(idx) {
+            let world_root = self.world.root();
+            let mut burst_translation = glam::Vec3::ZERO;
+            if let Some(transform) = self.world.get_mut::<Transform>(item_box) {
+                burst_translation = transform.translation;
+                transform.scale = if available {
+                    glam::Vec3::ONE * Self::ITEM_BOX_SCALE
+                } else {
+                    glam::Vec3::ZERO
+                };
+            }
+
+            if !available && GLOBAL_CONFIG.enable_particle_effects {
+                let burst_transform = Transform {
+                    translation: burst_translation,
+                    rotation: glam::Quat::IDENTITY,
+                    scale: glam::Vec3::ONE * Self::ITEM_BOX_SCALE,
+                };
+                // pretend a bunch of time has passed so a whole burst comes out at once
+                self.smoke_particle_system.spawn(
+                    &self.renderer,
+                    &mut self.world,
+                    &burst_transform,
+                    world_root,
+                    0.3,
+                );
+            }
+        }
+    }
+
     pub fn update(&mut self, delta_time: f32) {
+        // keep pointing out whoever the audience singled out while the effect lasts
+        let highlighted_players = match &self.ui {
+            UIState::InGameHUD {
+                interaction_state:
+                    InteractionState::Active {
+                        targets, choice, ..
+                    },
+                ..
+            } if choice.target != AudienceTarget::Everyone => targets.clone(),
+            _ => vec![],
+        };
+        for player_num in highlighted_players {
+            self.highlight_player(player_num, delta_time);
+        }
+
+        // sparks fly off the back of anyone drifting
+        if GLOBAL_CONFIG.enable_particle_effects {
+            for player_num in 0..4 {
+                if self.drift_charges[player_num].is_some() {
+                    self.add_drift_sparks_to_player(player_num as PlayerID, delta_time);
+                }
+            }
+        }
+
+        let item_box_spin = glam::Quat::from_rotation_y(Self::ITEM_BOX_SPIN_SPEED * delta_time);
+        for &item_box in &self.item_boxes {
+            if let Some(transform) = self.world.get_mut::<Transform>(item_box) {
+                transform.rotation = item_box_spin * transform.rotation;
+            }
+        }
+
+        // shock 'em bolts leave a crackling trail behind them
+        let bolts: Vec<Entity> = self
+            .world_entities
+            .values()
+            .filter(|(_, kind)| *kind == WorldEntityKind::ShockEmBolt)
+            .map(|(entity, _)| *entity)
+            .collect();
+        let world_root = self.world.root();
+        for bolt in bolts {
+            if let Some(&bolt_transform) = self.world.get::<Transform>(bolt) {
+                self.shock_particle_system.spawn(
+                    &self.renderer,
+                    &mut self.world,
+                    &bolt_transform,
+                    world_root,
+                    delta_time,
+                );
+            }
+        }
+
         self.fire_particle_system
             .update(&mut self.world, delta_time);
         self.smoke_particle_system
             .update(&mut self.world, delta_time);
+        self.shock_particle_system
+            .update(&mut self.world, delta_time);
     }
 
     pub fn update_flycam_angle(&mut self, x: f64, y: f64) {
@@ -636,16 +886,19 @@ impl GraphicsManager {
                     // Thus, the axis is the "right-left" of velocity
                     // This is what we get here
                     let axis = velocity.as_vec3().cross(glam::Vec3::Y).normalize();
+                    // velocity is per second, and we roll as far as we moved since the last update
+                    let distance_this_tick =
+                        velocity.length() * GLOBAL_CONFIG.server_tick_ms as f64 / 1000.0;
                     drawable.modifiers.rotation = Some(
                         glam::Quat::from_axis_angle(
                             axis,
-                            // For the angle, we want to move the velocity's length
+                            // For the angle, we want to move the distance we rolled
                             // But we could go either + or - that amount
                             // We want to go "towards the ground"
                             // So we figure out if the angle between velocity and the Y axis is + or -
                             // And then go from there!
                             -(axis.angle_between(-glam::Vec3::Y)).signum()
-                                * velocity.length() as f32,
+                                * distance_this_tick as f32,
                         )
                         .normalize()
                         .mul_quat(drawable.modifiers.rotation.unwrap_or_default()),
@@ -728,6 +981,57 @@ impl GraphicsManager {
         );
     }
 
+    pub fn add_drift_sparks_to_player(&mut self, player_num: PlayerID, delta_time: f32) {
+        if let Some(player_entity) = self.player_entities[player_num as usize] {
+            let spark_transform = Transform {
+                translation: glam::Vec3::Z * -1.5,
+                rotation: glam::Quat::IDENTITY,
+                scale: glam::Vec3::ONE * 0.5,
+            };
+            self.shock_particle_system.spawn(
+                &self.renderer,
+                &mut self.world,
+                &spark_transform,
+                player_entity,
+                delta_time,
+            );
+        }
+    }
+
+    // where a point in the world showed up on screen last frame (in UI
+    // coordinates), if it was in view at all
+    pub fn world_to_screen(&self, position: glam::Vec3) -> Option<Vec2> {
+        let clip = self.prev_proj * self.prev_view * position.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+
+        let ndc = clip.truncate() / clip.w;
+        if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
+            return None;
+        }
+
+        Some(Vec2::new((ndc.x + 1.0) / 2.0, (1.0 - ndc.y) / 2.0))
+    }
+
+    // puff smoke out of the top of a player's chair so everyone can see them
+    pub fn highlight_player(&mut self, player_num: PlayerID, delta_time: f32) {
+        if let Some(player_entity) = self.player_entities[player_num as usize] {
+            let highlight_transform = Transform {
+                translation: glam::Vec3::Y * 6.0,
+                rotation: glam::Quat::IDENTITY,
+                scale: glam::Vec3::ONE,
+            };
+            self.smoke_particle_system.spawn(
+                &self.renderer,
+                &mut self.world,
+                &highlight_transform,
+                player_entity,
+                delta_time,
+            );
+        }
+    }
+
     pub fn render(&mut self) {
         self.update_dynamic_ui();
 
@@ -886,6 +1190,13 @@ impl GraphicsManager {
                 {
                     drawable.update_model(&self.renderer, acc_model * particle_model);
                 }
+
+                if let Some(particle_model) =
+                    self.shock_particle_system
+                        .calc_particle_model(&self.world, e, view)
+                {
+                    drawable.update_model(&self.renderer, acc_model * particle_model);
+                }
             }
 
             acc_model
@@ -953,6 +1264,7 @@ impl GraphicsManager {
                 chair_description,
                 player_chair_images,
                 chair_readiness,
+                track_vote_text,
             } => {
                 let background_graph = background.render_graph(&render_context);
                 render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, background_graph);
@@ -975,17 +1287,29 @@ impl GraphicsManager {
                 let chair_description_box_graph = chair_description.render_graph(&render_context);
                 render_job
                     .merge_graph_after(SimpleFSQTechnique::PASS_NAME, chair_description_box_graph);
+
+                let track_vote_graph = track_vote_text.render_graph(&render_context);
+                render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, track_vote_graph);
             }
             UIState::InGameHUD {
                 place_position_image,
                 minimap_ui,
                 timer_ui,
                 lap_ui,
+                gap_ui,
+                lap_time_ui,
+                best_lap_ui,
                 interaction_ui,
                 countdown_ui,
                 interaction_text,
                 join_the_audience_image,
                 finished_text,
+                emotes,
+                powerup_image,
+                drift_meter,
+                respawn_fade,
+                wrong_way_text,
+                wrong_way_since,
                 ..
             } => {
                 let position_graph = place_position_image.render_graph(&render_context);
@@ -997,6 +1321,15 @@ impl GraphicsManager {
                 let lap_ui_graph = lap_ui.render_graph(&render_context);
                 render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, lap_ui_graph);
 
+                let gap_ui_graph = gap_ui.render_graph(&render_context);
+                render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, gap_ui_graph);
+
+                let lap_time_graph = lap_time_ui.render_graph(&render_context);
+                render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, lap_time_graph);
+
+                let best_lap_graph = best_lap_ui.render_graph(&render_context);
+                render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, best_lap_graph);
+
                 let timer_ui_graph = timer_ui.render_graph(&render_context);
                 render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, timer_ui_graph);
 
@@ -1017,6 +1350,28 @@ impl GraphicsManager {
 
                 let finished_text_graph = finished_text.render_graph(&render_context);
                 render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, finished_text_graph);
+
+                let powerup_graph = powerup_image.render_graph(&render_context);
+                render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, powerup_graph);
+
+                let drift_meter_graph = drift_meter.render_graph(&render_context);
+                render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, drift_meter_graph);
+
+                if let Some(since) = wrong_way_since {
+                    if Self::is_flashed_on(*since) {
+                        let wrong_way_graph = wrong_way_text.render_graph(&render_context);
+                        render_job
+                            .merge_graph_after(SimpleFSQTechnique::PASS_NAME, wrong_way_graph);
+                    }
+                }
+
+                let respawn_fade_graph = respawn_fade.render_graph(&render_context);
+                render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, respawn_fade_graph);
+
+                for emote in emotes {
+                    let emote_graph = emote.text.render_graph(&render_context);
+                    render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, emote_graph);
+                }
             }
             UIState::MainMenu { background } => {
                 let ui_graph = background.render_graph(&render_context);
@@ -1025,6 +1380,7 @@ impl GraphicsManager {
             UIState::FinalStandings {
                 final_standings_ui,
                 player_final_times,
+                player_best_laps,
             } => {
                 let ui_graph = final_standings_ui.render_graph(&render_context);
                 render_job.merge_graph_after(SimpleFSQTechnique::PASS_NAME, ui_graph);
@@ -1033,6 +1389,11 @@ impl GraphicsManager {
                     let time_graph = player_final_time.render_graph(&render_context);
                     render_job.merge_graph_after(UILayerTechnique::PASS_NAME, time_graph);
                 }
+
+                for player_best_lap in player_best_laps {
+                    let best_lap_graph = player_best_lap.render_graph(&render_context);
+                    render_job.merge_graph_after(UILayerTechnique::PASS_NAME, best_lap_graph);
+                }
             }
         }
 
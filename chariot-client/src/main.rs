@@ -39,7 +39,7 @@ fn main() {
     if dev_mode {
         println!("Running in dev mode...");
         let mut pressed_keys = HashSet::new();
-        graphics_manager.load_dev_mode(Track::Track);
+        graphics_manager.load_dev_mode(Track::default());
         event_loop.run(move |event, _, control_flow| {
             // TRIGGER EVENTS
             *control_flow = ControlFlow::Poll;
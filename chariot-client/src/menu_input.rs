@@ -31,6 +31,22 @@ impl Application {
         );
     }
 
+    fn vote_for_next_track(&mut self) {
+        if let Some(track) = self.graphics.next_track_vote() {
+            if let Some(choices) = self.graphics.player_choices[self.graphics.player_num].as_mut() {
+                choices.map = track.clone();
+            }
+            self.game.pick_map(track);
+            self.graphics.lobby_notice = None;
+            self.graphics.maybe_update_track_vote();
+            self.sfx_manager.play(
+                get_sfx(chariot_core::sound_effect::SoundEffect::SelectChairacter),
+                &self.audio_context,
+                SourceOptions::new(),
+            );
+        }
+    }
+
     fn input_gamepad_chairacter_select(&mut self, event: Result<(Button, f32), (Axis, f32)>) {
         if let Ok((button, value)) = event {
             match button {
@@ -67,6 +83,7 @@ impl Application {
                         SourceOptions::new(),
                     );
                 }
+                Button::DPadDown if value == 1.0 => self.vote_for_next_track(),
                 Button::DPadLeft if value == 1.0 => {
                     let new_chair = match self.graphics.player_choices[self.graphics.player_num]
                         .as_ref()
@@ -131,6 +148,8 @@ impl Application {
                 &self.audio_context,
                 SourceOptions::new(),
             );
+        } else if key == VirtualKeyCode::Down {
+            self.vote_for_next_track();
         } else if key == VirtualKeyCode::Up {
             self.game.signal_ready_status(true);
             self.sfx_manager.play(
@@ -157,6 +176,9 @@ impl Application {
                     Some(InputEvent::Engine(EngineStatus::Braking))
                 }
                 Button::LeftTrigger2 => Some(InputEvent::Engine(EngineStatus::Neutral)),
+
+                // either shoulder button drifts
+                Button::LeftTrigger | Button::RightTrigger => Some(InputEvent::Drift(value > 0.0)),
                 _ => None,
             },
             // Axis (value: [-1, 1])
@@ -174,6 +196,20 @@ impl Application {
     }
 
     fn input_gamepad_in_game(&mut self, event: Result<(Button, f32), (Axis, f32)>) {
+        if let Ok((Button::South, value)) = event {
+            if value == 1.0 {
+                self.game.use_powerup();
+            }
+            return;
+        }
+
+        if let Ok((Button::North, value)) = event {
+            if value == 1.0 {
+                self.game.request_respawn();
+            }
+            return;
+        }
+
         if let Some(valid_input_event) = self.get_gamepad_input_event_in_game(event) {
             self.game.send_input_event(valid_input_event);
         }
@@ -192,11 +228,23 @@ impl Application {
             )),
             // Right
             VirtualKeyCode::D => Some(InputEvent::Rotation(RotationStatus::InSpinClockwise(1.0))),
+            // Drift
+            VirtualKeyCode::LShift => Some(InputEvent::Drift(true)),
             _ => None,
         }
     }
 
     fn input_keyboard_in_game(&mut self, key: VirtualKeyCode) {
+        if key == VirtualKeyCode::Space {
+            self.game.use_powerup();
+            return;
+        }
+
+        if key == VirtualKeyCode::Back {
+            self.game.request_respawn();
+            return;
+        }
+
         if let Some(event) = self.get_keyboard_input_event_in_game(key) {
             self.game.send_input_event(event);
         };
@@ -300,6 +348,7 @@ impl Application {
             Some(InputEvent::Rotation(RotationStatus::InSpinCounterclockwise(_))) => {
                 InputEvent::Rotation(RotationStatus::NotInSpin)
             }
+            Some(InputEvent::Drift(true)) => InputEvent::Drift(false),
             _ => return None,
         })
     }
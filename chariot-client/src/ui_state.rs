@@ -5,9 +5,12 @@ use glam::Vec2;
 use image::ImageFormat;
 use lazy_static::lazy_static;
 
-use chariot_core::player::choices::Chair;
-use chariot_core::player::lap_info::Placement;
-use chariot_core::questions::{QuestionData, QuestionOption};
+use chariot_core::networking::ws::{Emote, TrackRect};
+use chariot_core::player::choices::{Chair, Track};
+use chariot_core::player::lap_info::{LapNumber, LapTime, Placement};
+use chariot_core::player::PlayerID;
+use chariot_core::powerup::PowerUp;
+use chariot_core::questions::{AudienceTarget, QuestionData, QuestionOption};
 
 use crate::assets::ui::get_chair_icon;
 use crate::drawable::AnimatedUIDrawable;
@@ -40,9 +43,16 @@ pub enum InteractionState {
         choice: QuestionOption,
         end_time: Instant,
         bar_filled: bool,
+        targets: Vec<PlayerID>,
     },
 }
 
+// An audience reaction drifting up the screen
+pub struct FloatingEmote {
+    pub text: AnimatedUIDrawable,
+    pub expire_time: Instant,
+}
+
 pub enum UIState {
     None,
     MainMenu {
@@ -54,6 +64,7 @@ pub enum UIState {
         chair_description: UIDrawable,
         player_chair_images: Vec<Option<UIDrawable>>,
         chair_readiness: [Option<UIDrawable>; 4],
+        track_vote_text: UIDrawable,
     },
     InGameHUD {
         countdown_ui: Option<UIDrawable>,
@@ -62,18 +73,37 @@ pub enum UIState {
         minimap_ui: UIDrawable,
         timer_ui: UIDrawable,
         lap_ui: UIDrawable,
+        gap_ui: UIDrawable,
+        lap_time_ui: UIDrawable,
+        best_lap_ui: UIDrawable,
         interaction_ui: AnimatedUIDrawable,
         interaction_text: UIDrawable,
         interaction_state: InteractionState,
         join_the_audience_image: UIDrawable,
         finished_text: UIDrawable,
+        emotes: Vec<FloatingEmote>,
+        powerup_image: UIDrawable,
+        drift_meter: UIDrawable,
+        respawn_fade: AnimatedUIDrawable,
+        wrong_way_text: UIDrawable,
+        wrong_way_since: Option<Instant>, // None if we're going the right way
     },
     FinalStandings {
         final_standings_ui: UIDrawable,
         player_final_times: [UIDrawable; 4],
+        player_best_laps: [UIDrawable; 4],
     },
 }
 
+// what the minimap covers on our original track, for if the server hasn't told
+// us about the one we're on
+const DEFAULT_MINIMAP_BOUNDS: TrackRect = TrackRect {
+    min_x: -119.0,
+    max_x: 44.0,
+    min_z: -48.0,
+    max_z: 119.0,
+};
+
 // by initializing the builders statically,
 // we can quickly clone then and change their content to regenerate drawables
 lazy_static! {
@@ -123,15 +153,54 @@ lazy_static! {
         .alignment(StringAlignment::LEFT)
         .content(format!("lap 1/{}", GLOBAL_CONFIG.number_laps).as_str())
         .position(30.0 / 1280.0, 0.35);
+    static ref GAP_TEXT: UIStringBuilder = UIStringBuilder::new(*assets::fonts::LAP_TEXT_FONT)
+        .alignment(StringAlignment::LEFT)
+        .content("")
+        .position(30.0 / 1280.0, 0.42);
+    static ref LAP_TIME_TEXT: UIStringBuilder = UIStringBuilder::new(*assets::fonts::LAP_TEXT_FONT)
+        .alignment(StringAlignment::LEFT)
+        .content("")
+        .position(30.0 / 1280.0, 0.49);
+    static ref BEST_LAP_TEXT: UIStringBuilder = UIStringBuilder::new(*assets::fonts::LAP_TEXT_FONT)
+        .alignment(StringAlignment::LEFT)
+        .content("")
+        .position(30.0 / 1280.0, 0.56);
+    static ref FINAL_BEST_LAP_TEXT: UIStringBuilder =
+        UIStringBuilder::new(*assets::fonts::LAP_TEXT_FONT)
+            .alignment(StringAlignment::LEFT)
+            .content("");
     static ref INTERACTION_TEXT: UIStringBuilder =
         UIStringBuilder::new(*assets::fonts::LAP_TEXT_FONT)
             .alignment(StringAlignment::CENTERED)
             .content("The audience is deciding your fate...");
+    static ref EMOTE_TEXT: UIStringBuilder = UIStringBuilder::new(*assets::fonts::LAP_TEXT_FONT)
+        .alignment(StringAlignment::CENTERED)
+        .content("");
+    static ref TRACK_VOTE_TEXT: UIStringBuilder =
+        UIStringBuilder::new(*assets::fonts::LAP_TEXT_FONT)
+            .alignment(StringAlignment::CENTERED)
+            .content("")
+            .position(0.5, 0.04);
     static ref FINISHED_TEXT: UIStringBuilder =
         UIStringBuilder::new(*assets::fonts::PLACEMENT_TEXT_FONT)
             .alignment(StringAlignment::CENTERED)
             .position(0.5, 0.5)
             .content("");
+    static ref WRONG_WAY_TEXT: UIStringBuilder =
+        UIStringBuilder::new(*assets::fonts::PLACEMENT_TEXT_FONT)
+            .alignment(StringAlignment::CENTERED)
+            .position(0.5, 0.3)
+            .content("WRONG WAY!");
+}
+
+// minutes:seconds:millis, like the race timer
+fn format_race_time(time: Duration) -> String {
+    format!(
+        "{:02}:{:02}:{:03}",
+        time.as_secs() / 60,
+        time.as_secs() % 60,
+        time.subsec_millis()
+    )
 }
 
 impl GraphicsManager {
@@ -140,10 +209,7 @@ impl GraphicsManager {
             ref mut timer_ui, ..
         } = self.ui
         {
-            let minutes = time_elapsed.as_secs() / 60;
-            let seconds = time_elapsed.as_secs() % 60;
-            let millis = time_elapsed.subsec_millis();
-            let time = format!("{:02}:{:02}:{:03}", minutes, seconds, millis);
+            let time = format_race_time(time_elapsed);
             *timer_ui = TIMER_TEXT
                 .clone()
                 .content(&time)
@@ -158,11 +224,28 @@ impl GraphicsManager {
             interaction_ui,
             interaction_text,
             interaction_state,
+            emotes,
+            respawn_fade,
             ..
         } = &mut self.ui
         {
             interaction_ui.update(&mut self.renderer);
 
+            respawn_fade.update(&mut self.renderer);
+            if respawn_fade
+                .layers
+                .iter()
+                .all(|(_, _, _, color_animation)| color_animation.is_none())
+            {
+                respawn_fade.layers.clear();
+            }
+
+            let now = Instant::now();
+            emotes.retain(|emote| emote.expire_time > now);
+            for emote in emotes.iter_mut() {
+                emote.text.update(&mut self.renderer);
+            }
+
             if let InteractionState::Active {
                 end_time,
                 bar_filled,
@@ -203,19 +286,21 @@ impl GraphicsManager {
                 return;
             }
 
-            // Convert "map units" locations into proportions of minimap size
-            fn get_minimap_player_location(location: (f32, f32)) -> (f32, f32) {
-                // these values are guesses btw
-                const MIN_TRACK_X: f32 = -119.0; // top
-                const MAX_TRACK_X: f32 = 44.0; // bottom
-                const MIN_TRACK_Z: f32 = -48.0; // right
-                const MAX_TRACK_Z: f32 = 119.0; // left
+            // the track tells us how much of the world the minimap covers
+            let bounds = self
+                .track_metadata
+                .as_ref()
+                .map(|metadata| metadata.minimap_bounds)
+                .unwrap_or(DEFAULT_MINIMAP_BOUNDS);
 
+            // Convert "map units" locations into proportions of minimap size;
+            // min x is the top, max z is the left
+            let get_minimap_player_location = |location: (f32, f32)| -> (f32, f32) {
                 (
-                    (MAX_TRACK_Z - location.1) / (MAX_TRACK_Z - MIN_TRACK_Z),
-                    (location.0 - MIN_TRACK_X) / (MAX_TRACK_X - MIN_TRACK_X),
+                    (bounds.max_z - location.1) / (bounds.max_z - bounds.min_z),
+                    (location.0 - bounds.min_x) / (bounds.max_x - bounds.min_x),
                 )
-            }
+            };
 
             let player_locations = self
                 .player_entities
@@ -350,6 +435,7 @@ impl GraphicsManager {
         choice: QuestionOption,
         duration: Duration,
         victor_idx: usize,
+        targets: Vec<PlayerID>,
     ) {
         if let UIState::InGameHUD {
             interaction_ui,
@@ -376,9 +462,26 @@ impl GraphicsManager {
             let last = interaction_ui.layers.len() - 1;
             interaction_ui.layers.swap(victor_idx, last);
 
+            // let everyone know who got singled out, if anyone did
+            let description = match choice.target {
+                AudienceTarget::Everyone => choice.action.get_description().to_string(),
+                _ if targets.contains(&self.player_num) => {
+                    format!("{} (that's you!)", choice.action.get_description())
+                }
+                _ => format!(
+                    "{} (hit: {})",
+                    choice.action.get_description(),
+                    targets
+                        .iter()
+                        .map(|target| format!("P{}", target + 1))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            };
+
             *interaction_text = INTERACTION_TEXT
                 .clone()
-                .content(choice.action.get_description())
+                .content(&description)
                 .position(0.5, Self::INTERACTION_VOTING_Y_POS / 1.5)
                 .build_drawable(&self.renderer, &mut self.resources);
             *interaction_state = InteractionState::Active {
@@ -386,15 +489,219 @@ impl GraphicsManager {
                 choice,
                 end_time: Instant::now() + duration,
                 bar_filled: false,
+                targets,
+            };
+        }
+    }
+
+    const EMOTE_FLOAT_DURATION: Duration = Duration::from_millis(2000);
+    const EMOTE_FLOAT_DISTANCE: f32 = 0.15;
+    const EMOTE_ICON_WIDTH: f32 = 0.04;
+    const EMOTE_ICON_HEIGHT: f32 = 0.07;
+
+    // aim at the given chair if it's on screen, otherwise somewhere along the
+    // bottom of the screen
+    fn float_text_start_pos(&self, target: Option<PlayerID>) -> Vec2 {
+        target
+            .and_then(|player_num| self.player_entities[player_num])
+            .and_then(|entity| self.world.get::<Transform>(entity))
+            .and_then(|transform| self.world_to_screen(transform.translation + glam::Vec3::Y * 3.0))
+            .unwrap_or_else(|| Vec2::new(0.3 + rand::random::<f32>() * 0.4, 0.85))
+    }
+
+    // shows some text that drifts up the screen for a couple of seconds
+    fn float_text(&mut self, content: &str, start_pos: Vec2) {
+        let glyphs = EMOTE_TEXT
+            .clone()
+            .content(content)
+            .position(start_pos.x, start_pos.y)
+            .build_drawable(&self.renderer, &mut self.resources);
+        self.float_layers(glyphs.layers);
+    }
+
+    // shows an emote's icon drifting up the screen, with how many of them
+    // there were next to it
+    fn float_emote(&mut self, emote: Emote, count: u32, start_pos: Vec2) {
+        let icon_handle = self.resources.import_texture_embedded(
+            &self.renderer,
+            emote.get_label(),
+            assets::sprites::get_emote_icon(emote),
+            ImageFormat::Png,
+        );
+        let icon_texture = self
+            .resources
+            .textures
+            .get(&icon_handle)
+            .expect("Expected emote image!");
+
+        let icon_size = Vec2::new(Self::EMOTE_ICON_WIDTH, Self::EMOTE_ICON_HEIGHT);
+        let mut layers = vec![UILayerTechnique::new(
+            &self.renderer,
+            start_pos - icon_size / 2.0,
+            icon_size,
+            glam::vec2(0.0, 0.0),
+            glam::vec2(1.0, 1.0),
+            icon_texture,
+        )];
+
+        if count > 1 {
+            let count_text = EMOTE_TEXT
+                .clone()
+                .alignment(StringAlignment::LEFT)
+                .content(format!("x{}", count).as_str())
+                .position(start_pos.x + icon_size.x / 2.0, start_pos.y)
+                .build_drawable(&self.renderer, &mut self.resources);
+            layers.extend(count_text.layers);
+        }
+
+        self.float_layers(layers);
+    }
+
+    fn float_layers(&mut self, layers: Vec<UILayerTechnique>) {
+        if let UIState::InGameHUD { emotes, .. } = &mut self.ui {
+            let mut text = AnimatedUIDrawable::new();
+            for layer in layers {
+                let end_pos = layer.pos - Vec2::new(0.0, Self::EMOTE_FLOAT_DISTANCE);
+                text.push(layer);
+                text.pos_to(text.layers.len() - 1, end_pos, Self::EMOTE_FLOAT_DURATION);
+            }
+
+            emotes.push(FloatingEmote {
+                text,
+                expire_time: Instant::now() + Self::EMOTE_FLOAT_DURATION,
+            });
+        }
+    }
+
+    pub fn show_audience_emotes(&mut self, new_emotes: Vec<(Emote, Option<PlayerID>, u32)>) {
+        for (emote, target, count) in new_emotes {
+            let start_pos = self.float_text_start_pos(target);
+            self.float_emote(emote, count, start_pos);
+        }
+    }
+
+    pub fn display_held_powerup(&mut self, powerup: Option<PowerUp>) {
+        if let UIState::InGameHUD { powerup_image, .. } = &mut self.ui {
+            *powerup_image = match powerup {
+                None => UIDrawable { layers: vec![] },
+                Some(powerup) => {
+                    let powerup_handle = self.resources.import_texture_embedded(
+                        &self.renderer,
+                        powerup.get_name(),
+                        assets::sprites::get_powerup_icon(powerup),
+                        ImageFormat::Png,
+                    );
+
+                    let powerup_texture = self
+                        .resources
+                        .textures
+                        .get(&powerup_handle)
+                        .expect("Expected powerup image!");
+
+                    UIDrawable {
+                        layers: vec![UILayerTechnique::new(
+                            &self.renderer,
+                            glam::vec2(1117.0 / 1280.0, 34.0 / 720.0),
+                            glam::vec2(0.1, 0.15),
+                            glam::vec2(0.0, 0.0),
+                            glam::vec2(1.0, 1.0),
+                            &powerup_texture,
+                        )],
+                    }
+                }
             };
         }
     }
 
+    pub fn show_powerup_activated(
+        &mut self,
+        user: PlayerID,
+        powerup: PowerUp,
+        targets: Vec<PlayerID>,
+    ) {
+        let describe = |player_num: PlayerID| {
+            if player_num == self.player_num {
+                "you".to_string()
+            } else {
+                format!("P{}", player_num + 1)
+            }
+        };
+
+        let content = match targets.first() {
+            Some(&target) if target != user => format!(
+                "{} used {} on {}!",
+                describe(user),
+                powerup.get_name(),
+                describe(target)
+            ),
+            _ => format!("{} used {}!", describe(user), powerup.get_name()),
+        };
+
+        let start_pos = self.float_text_start_pos(targets.first().copied().or(Some(user)));
+        self.float_text(&content, start_pos);
+    }
+
+    // how many laps this track is, as far as we've heard
+    fn lap_count(&self) -> LapNumber {
+        self.track_metadata
+            .as_ref()
+            .map(|metadata| metadata.laps)
+            .unwrap_or(GLOBAL_CONFIG.number_laps)
+    }
+
     pub fn maybe_update_lap(&mut self, lap: u8) {
+        let lap_count = self.lap_count();
         if let UIState::InGameHUD { ref mut lap_ui, .. } = self.ui {
             *lap_ui = LAP_TEXT
                 .clone()
-                .content(format!("lap {}/{}", lap, GLOBAL_CONFIG.number_laps).as_str())
+                .content(format!("lap {}/{}", lap, lap_count).as_str())
+                .build_drawable(&self.renderer, &mut self.resources);
+        }
+    }
+
+    // how far behind the player ahead of us we are, or nothing if there's
+    // nobody ahead to catch
+    pub fn update_gap(&mut self, gap: Option<f32>) {
+        if let UIState::InGameHUD { ref mut gap_ui, .. } = self.ui {
+            match gap {
+                Some(gap) => {
+                    *gap_ui = GAP_TEXT
+                        .clone()
+                        .content(format!("gap +{:.0}m", gap).as_str())
+                        .build_drawable(&self.renderer, &mut self.resources);
+                }
+                None => gap_ui.layers.clear(),
+            }
+        }
+    }
+
+    // how the lap we just finished went, next to our best one
+    pub fn show_lap_time(&mut self, lap_time: &LapTime, best_lap: Duration, delta: Option<f32>) {
+        if let UIState::InGameHUD {
+            ref mut lap_time_ui,
+            ref mut best_lap_ui,
+            ..
+        } = self.ui
+        {
+            let delta = match delta {
+                Some(delta) => format!(" ({:+.3})", delta),
+                None => String::new(),
+            };
+            *lap_time_ui = LAP_TIME_TEXT
+                .clone()
+                .content(
+                    format!(
+                        "lap {} {}{}",
+                        lap_time.lap,
+                        format_race_time(lap_time.time),
+                        delta
+                    )
+                    .as_str(),
+                )
+                .build_drawable(&self.renderer, &mut self.resources);
+            *best_lap_ui = BEST_LAP_TEXT
+                .clone()
+                .content(format!("best {}", format_race_time(best_lap)).as_str())
                 .build_drawable(&self.renderer, &mut self.resources);
         }
     }
@@ -503,6 +810,108 @@ impl GraphicsManager {
         None
     }
 
+    // bottom middle of the screen, the empty bar is layer 0 and the fill is layer 1
+    const DRIFT_METER_X_POS: f32 = 0.4;
+    const DRIFT_METER_Y_POS: f32 = 0.9;
+    const DRIFT_METER_WIDTH: f32 = 0.2;
+    const DRIFT_METER_HEIGHT: f32 = 0.025;
+    const DRIFT_METER_EMPTY_COLOR: [f32; 4] = [0.1, 0.1, 0.1, 0.6];
+    const DRIFT_METER_CHARGING_COLOR: [f32; 4] = [0.6, 0.8, 1.0, 1.0];
+    const DRIFT_METER_READY_COLOR: [f32; 4] = [1.0, 0.6, 0.1, 1.0];
+    // matches the least the server will give a boost for
+    const DRIFT_METER_READY_CHARGE: f32 = 0.25;
+
+    // None hides the meter, since we aren't drifting
+    pub fn update_drift_meter(&mut self, charge: Option<f32>) {
+        if let UIState::InGameHUD { drift_meter, .. } = &mut self.ui {
+            if charge.is_none() {
+                drift_meter.layers.clear();
+                return;
+            }
+
+            if drift_meter.layers.is_empty() {
+                for color in [
+                    Self::DRIFT_METER_EMPTY_COLOR,
+                    Self::DRIFT_METER_CHARGING_COLOR,
+                ] {
+                    let mut layer = UILayerTechnique::new(
+                        &self.renderer,
+                        glam::vec2(Self::DRIFT_METER_X_POS, Self::DRIFT_METER_Y_POS),
+                        glam::vec2(Self::DRIFT_METER_WIDTH, Self::DRIFT_METER_HEIGHT),
+                        glam::vec2(0.0, 0.0),
+                        glam::vec2(1.0, 1.0),
+                        self.resources.textures.get(&self.white_box_tex).unwrap(),
+                    );
+                    layer.update_color(&self.renderer, color);
+                    drift_meter.layers.push(layer);
+                }
+            }
+
+            let charge = charge.unwrap().clamp(0.0, 1.0);
+            let color = if charge >= Self::DRIFT_METER_READY_CHARGE {
+                Self::DRIFT_METER_READY_COLOR
+            } else {
+                Self::DRIFT_METER_CHARGING_COLOR
+            };
+            let fill = &mut drift_meter.layers[1];
+            fill.update_size(
+                &self.renderer,
+                glam::vec2(Self::DRIFT_METER_WIDTH * charge, Self::DRIFT_METER_HEIGHT),
+            );
+            fill.update_color(&self.renderer, color);
+        }
+    }
+
+    // the wrong way warning spends this long on, then this long off
+    const WRONG_WAY_FLASH_PERIOD: Duration = Duration::from_millis(400);
+
+    pub fn set_wrong_way(&mut self, wrong_way: bool) {
+        if let UIState::InGameHUD {
+            wrong_way_text,
+            wrong_way_since,
+            ..
+        } = &mut self.ui
+        {
+            if !wrong_way {
+                *wrong_way_since = None;
+                wrong_way_text.layers.clear();
+            } else if wrong_way_since.is_none() {
+                *wrong_way_since = Some(Instant::now());
+                *wrong_way_text = WRONG_WAY_TEXT
+                    .clone()
+                    .build_drawable(&self.renderer, &mut self.resources);
+            }
+        }
+    }
+
+    // whether a warning that started flashing at `since` is showing right now
+    pub fn is_flashed_on(since: Instant) -> bool {
+        let periods = since.elapsed().as_millis() / Self::WRONG_WAY_FLASH_PERIOD.as_millis();
+        periods % 2 == 0
+    }
+
+    const RESPAWN_FADE_DURATION: Duration = Duration::from_millis(800);
+
+    // black the screen out and fade back in, so getting put back on the track
+    // isn't so jarring
+    pub fn start_respawn_fade(&mut self) {
+        if let UIState::InGameHUD { respawn_fade, .. } = &mut self.ui {
+            respawn_fade.layers.clear();
+
+            let mut blackout = UILayerTechnique::new(
+                &self.renderer,
+                glam::vec2(0.0, 0.0),
+                glam::vec2(1.0, 1.0),
+                glam::vec2(0.0, 0.0),
+                glam::vec2(1.0, 1.0),
+                self.resources.textures.get(&self.white_box_tex).unwrap(),
+            );
+            blackout.update_color(&self.renderer, [0.0, 0.0, 0.0, 1.0]);
+            respawn_fade.push(blackout);
+            respawn_fade.col_to(0, [0.0, 0.0, 0.0, 0.0], Self::RESPAWN_FADE_DURATION);
+        }
+    }
+
     pub fn display_finished_text(&mut self, placement: u8) {
         if let UIState::InGameHUD {
             ref mut finished_text,
@@ -625,12 +1034,18 @@ impl GraphicsManager {
 
         let chair_description = UIDrawable { layers: layer_vec };
 
+        let track_vote_text = TRACK_VOTE_TEXT
+            .clone()
+            .content(self.track_vote_summary().as_str())
+            .build_drawable(&self.renderer, &mut self.resources);
+
         self.ui = UIState::ChairacterSelect {
             background,
             chair_select_box,
             chair_description,
             player_chair_images: vec![None, None, None, None],
             chair_readiness: [None, None, None, None],
+            track_vote_text,
         };
 
         (0..4).for_each(|i| self.maybe_display_chair(None, i));
@@ -705,6 +1120,71 @@ impl GraphicsManager {
         }
     }
 
+    // what we're voting for and how popular it is, e.g. "Track: Office (2 votes)"
+    fn track_vote_summary(&self) -> String {
+        if let Some(notice) = &self.lobby_notice {
+            return notice.clone();
+        }
+
+        let my_vote = match &self.player_choices[self.player_num] {
+            Some(choices) => &choices.map,
+            None => return String::new(),
+        };
+
+        match self
+            .available_tracks
+            .iter()
+            .find(|info| &info.track == my_vote)
+        {
+            Some(info) => {
+                let votes = self
+                    .player_choices
+                    .iter()
+                    .flatten()
+                    .filter(|choices| &choices.map == my_vote)
+                    .count();
+                format!(
+                    "Track: {} ({} vote{})",
+                    info.display_name,
+                    votes,
+                    if votes == 1 { "" } else { "s" }
+                )
+            }
+            None if self.available_tracks.is_empty() => String::new(),
+            None => "Press down to vote for a track".to_string(),
+        }
+    }
+
+    // the track after the one we're currently voting for, wrapping around
+    pub fn next_track_vote(&self) -> Option<Track> {
+        let my_vote = self.player_choices[self.player_num]
+            .as_ref()
+            .map(|choices| &choices.map);
+        let next_idx = self
+            .available_tracks
+            .iter()
+            .position(|info| Some(&info.track) == my_vote)
+            .map(|idx| (idx + 1) % self.available_tracks.len())
+            .unwrap_or(0);
+
+        self.available_tracks
+            .get(next_idx)
+            .map(|info| info.track.clone())
+    }
+
+    pub fn maybe_update_track_vote(&mut self) {
+        let summary = self.track_vote_summary();
+        if let UIState::ChairacterSelect {
+            track_vote_text, ..
+        } = &mut self.ui
+        {
+            *track_vote_text = TRACK_VOTE_TEXT
+                .clone()
+                .content(summary.as_str())
+                .build_drawable(&self.renderer, &mut self.resources);
+        }
+    }
+
     pub fn maybe_set_chair_readiness(&mut self, chair_num: usize, ready: bool) {
         if let UIState::ChairacterSelect {
             chair_readiness, ..
@@ -803,6 +1283,7 @@ impl GraphicsManager {
 
         let lap_ui = LAP_TEXT
             .clone()
+            .content(format!("lap 1/{}", self.lap_count()).as_str())
             .build_drawable(&self.renderer, &mut self.resources);
 
         // minimap
@@ -889,11 +1370,20 @@ impl GraphicsManager {
             countdown_ui: None,
             countdown_state: CountdownState::None,
             lap_ui,
+            gap_ui: UIDrawable { layers: vec![] },
+            lap_time_ui: UIDrawable { layers: vec![] },
+            best_lap_ui: UIDrawable { layers: vec![] },
             interaction_ui: AnimatedUIDrawable::new(),
             interaction_text: UIDrawable { layers: vec![] },
             interaction_state: InteractionState::None,
             join_the_audience_image,
             finished_text: UIDrawable { layers: vec![] },
+            emotes: vec![],
+            powerup_image: UIDrawable { layers: vec![] },
+            drift_meter: UIDrawable { layers: vec![] },
+            respawn_fade: AnimatedUIDrawable::new(),
+            wrong_way_text: UIDrawable { layers: vec![] },
+            wrong_way_since: None,
         }
     }
 
@@ -902,6 +1392,7 @@ impl GraphicsManager {
         positions: [u8; 4],
         chairs: [Chair; 4],
         times: [(u64, u32); 4],
+        best_laps: [Option<Duration>; 4],
     ) {
         let background_handle = self.resources.import_texture_embedded(
             &self.renderer,
@@ -1019,11 +1510,8 @@ impl GraphicsManager {
         let final_standings_ui = UIDrawable { layers: layer_vec };
 
         let player_final_times = [0, 1, 2, 3].map(|player_index| {
-            let (time_secs, time_millis) = times[player_index];
-            let minutes = time_secs / 60;
-            let seconds = time_secs % 60;
-            let millis = time_millis % 1000;
-            let time_str = format!("{:02}:{:02}:{:03}", minutes, seconds, millis);
+            let (time_secs, time_nanos) = times[player_index];
+            let time_str = format_race_time(Duration::new(time_secs, time_nanos));
 
             let placement = positions[player_index];
 
@@ -1051,9 +1539,27 @@ impl GraphicsManager {
             }
         });
 
+        // each player's best lap goes just under their time
+        let player_best_laps = [0, 1, 2, 3].map(|player_index| {
+            let placement = positions[player_index].clamp(1, 4);
+            let content = match best_laps[player_index] {
+                Some(best_lap) => format!("best lap {}", format_race_time(best_lap)),
+                None => String::new(),
+            };
+            FINAL_BEST_LAP_TEXT
+                .clone()
+                .content(&content)
+                .position(
+                    666.0 / 1280.0,
+                    (255.0 + 100.0 * (placement - 1) as f32) / 720.0,
+                )
+                .build_drawable(&self.renderer, &mut self.resources)
+        });
+
         self.ui = UIState::FinalStandings {
             final_standings_ui,
             player_final_times,
+            player_best_laps,
         }
     }
 }
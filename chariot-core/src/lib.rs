@@ -1,8 +1,10 @@
 pub mod entity_location;
 pub mod networking;
 pub mod player;
+pub mod powerup;
 pub mod questions;
 mod settings;
 pub mod sound_effect;
+pub mod world_entity;
 
 pub use settings::GLOBAL_CONFIG;
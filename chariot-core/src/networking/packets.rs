@@ -9,14 +9,17 @@ use serde::{Deserialize, Serialize};
 pub use uuid::Uuid;
 
 use crate::entity_location::EntityLocation;
-use crate::player::choices::{Chair, PlayerChoices, Track};
+use crate::networking::ws::Emote;
+use crate::player::choices::{Chair, PlayerChoices, Track, TrackInfo, TrackMetadata};
 use crate::player::{
-    lap_info::{LapNumber, Placement},
+    lap_info::{LapNumber, LapTime, Placement},
     player_inputs::InputEvent,
     PlayerID,
 };
+use crate::powerup::PowerUp;
 use crate::questions::{QuestionData, QuestionOption};
 use crate::sound_effect::SoundEffect;
+use crate::world_entity::{WorldEntityID, WorldEntityKind};
 
 #[derive(Serialize, Deserialize)]
 pub enum ServerBoundPacket {
@@ -29,6 +32,8 @@ pub enum ServerBoundPacket {
 
     // During game
     InputToggle(InputEvent),
+    UsePowerup,     // Use whatever powerup we're holding
+    RequestRespawn, // Put us back at our last checkpoint
 
     // After game
     NextGame,
@@ -38,20 +43,44 @@ pub enum ServerBoundPacket {
 pub enum ClientBoundPacket {
     // Before game
     PlayerNumber(PlayerID, [Option<PlayerChoices>; 4]),
+    AvailableTracks(Vec<TrackInfo>), // Every track the server found, in the order to show them
     PlayerChairChoice(PlayerID, Chair), // Another player has hovered a chair
-    PlayerMapChoice(PlayerID, Track),   // Another player has hovered a map
-    PlayerReadyStatus(PlayerID, bool),  // Another player has readied or unreaded
+    PlayerMapChoice(PlayerID, Track), // Another player has hovered a map
+    PlayerReadyStatus(PlayerID, bool), // Another player has readied or unreaded
     PlayerJoined(PlayerID),
 
     // Load into the game
+    TrackLoadFailed {
+        track: Track,
+        reason: String,
+    }, // The voted track is broken; everyone's unreadied and it's off the list
     LoadGame(Track), // Map name, each player's chair
 
+    TrackLoaded(TrackMetadata), // Laps, minimap extents etc. of the track we're about to race on
+    ItemBoxesPlaced(Vec<DVec3>), // Where every item box on the map is, in index order
+
     // Pre-game
     GameStart(Duration), // How long until the game starts?
 
     // During game
     EntityUpdate(Vec<(EntityLocation, DVec3, bool)>), // Clients will need to know the location and velocity of every player
-    PowerupPickup,                                    // Add a payload here when appropriate
+    DriftUpdate([Option<f32>; 4]), // How full every player's drift meter is, or None if they aren't drifting
+    PlayerRespawned(PlayerID),     // This player was put back at their last checkpoint
+    WrongWay(bool),                // Whether you're (still) going the wrong way around the track
+    PowerupPickup(PowerUp),        // You picked up this powerup
+    ItemBoxAvailability(usize, bool), // An item box (by index) was taken or has come back
+    PowerupActivated {
+        user: PlayerID,
+        powerup: PowerUp,
+        targets: Vec<PlayerID>, // Who the powerup ended up affecting
+    },
+    WorldEntitySpawned {
+        id: WorldEntityID,
+        kind: WorldEntityKind,
+        location: EntityLocation,
+    },
+    WorldEntityUpdate(Vec<(WorldEntityID, EntityLocation)>), // Where every world entity that moved is now
+    WorldEntityDespawned(WorldEntityID),
     VotingStarted {
         question: QuestionData,
         #[serde(with = "serde_millis")]
@@ -64,11 +93,19 @@ pub enum ClientBoundPacket {
         #[serde(with = "serde_millis")]
         time_effect_is_live: Duration,
         winner_idx: usize,
+        targets: Vec<PlayerID>, // Which players the decision was applied to
     }, // Sent when the audience has voted on something
     VotingCooldown,
-    LapUpdate(LapNumber),       // What lap are you now on?
-    PlacementUpdate(Placement), // What place in the race are you now at?
-    FinishedLaps(Placement),    // You completed all laps, what place are you?
+    AudienceEmotes(Vec<(Emote, Option<PlayerID>, u32)>), // Reactions since the last batch: emote, who it's aimed at, how many
+    LapUpdate(LapNumber),                                // What lap are you now on?
+    PlacementUpdate(Placement),                          // What place in the race are you now at?
+    GapUpdate(Option<f32>), // How far along the track the player ahead of you is, or None if nobody's ahead you can catch
+    FinishedLaps(Placement), // You completed all laps, what place are you?
+    LapCompleted {
+        lap_time: LapTime,
+        best_lap: Duration,         // your fastest lap so far, this one included
+        delta_to_best: Option<f32>, // seconds slower (or negative: faster) than your best before this lap, if you had one
+    },
 
     SoundEffectEvent(SoundEffect),
 
@@ -76,6 +113,7 @@ pub enum ClientBoundPacket {
     AllDone {
         // [place, time: (seconds, nanoseconds)]
         placements: [(Placement, (u64, u32)); 4],
+        lap_times: [Vec<LapTime>; 4], // every lap each player finished, in order
     },
     StartNextGame,
 }
@@ -1,11 +1,21 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Error;
+use std::io::ErrorKind;
 use std::net::TcpStream;
+use std::time::Duration;
 use std::{collections::VecDeque, time::Instant};
+use tungstenite::handshake::server::NoCallback;
+use tungstenite::handshake::MidHandshake;
+pub use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
 pub use tungstenite::{accept, Message, WebSocket};
+use tungstenite::{Error as WSError, HandshakeError, ServerHandshake};
 pub use uuid::Uuid;
 
+use crate::player::lap_info::{LapNumber, ZoneID};
+use crate::player::PlayerID;
 use crate::questions::QuestionData;
+use crate::GLOBAL_CONFIG;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Standing {
@@ -13,6 +23,7 @@ pub struct Standing {
     pub chair: String,
     pub rank: u8,
     pub lap: u8,
+    pub progress: f32, // how much of the race they've done, from 0 to 1
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -21,6 +32,53 @@ pub struct QuestionResult {
     pub percentage: f32,
 }
 
+// A racer's location flattened onto the ground plane (x/z), for drawing a minimap
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct RacerPosition {
+    pub x: f32,
+    pub z: f32,
+    pub heading: f32, // radians; 0 faces +z, increasing towards +x
+    pub speed: f32,
+    pub lap: LapNumber,
+    pub zone: ZoneID,
+}
+
+// An axis-aligned rectangle on the ground plane
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct TrackRect {
+    pub min_x: f32,
+    pub max_x: f32,
+    pub min_z: f32,
+    pub max_z: f32,
+}
+
+// Quick reactions the audience can throw at the race (or at one racer)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Emote {
+    Cheer,
+    Boo,
+    Laugh,
+    Fire,
+}
+
+impl Emote {
+    pub fn get_label(&self) -> &str {
+        match self {
+            Emote::Cheer => "yay!",
+            Emote::Boo => "boo!",
+            Emote::Laugh => "haha",
+            Emote::Fire => "fire!",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub points: u32,
+    pub is_you: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub enum WSAudienceBoundMessage {
     Prompt {
@@ -32,6 +90,7 @@ pub enum WSAudienceBoundMessage {
     Winner {
         choice: usize,
         option_results: Vec<QuestionResult>, // percentages of all the winners
+        targets: Vec<PlayerID>,              // the players the winning choice was applied to
         #[serde(with = "serde_millis")]
         vote_effect_time: Instant,
     }, // The winning choice (tuple index)
@@ -44,61 +103,170 @@ pub enum WSAudienceBoundMessage {
         time: Instant,
     },
     AudienceCount(usize), // The number of connections to the audience
+
+    TrackOutline {
+        bounds: TrackRect,     // the extents of everything below
+        zones: Vec<TrackRect>, // the drivable sections of the track, in order
+        walls: Vec<TrackRect>, // everything you can crash into
+        finish_line: TrackRect,
+    }, // Sent once per loaded track
+    Positions([RacerPosition; 4]), // Throttled stream of where everyone is
+
+    PredictionsOpen(bool), // Whether podium predictions are currently being accepted
+    Leaderboard {
+        entries: Vec<LeaderboardEntry>, // every connected audience member, best first
+        points: u32,                    // how many points this audience member can still bet
+    },
 }
 
 #[derive(Serialize, Deserialize)]
 pub enum WSServerBoundMessage {
-    Vote(Uuid, usize), // Client UUID, the option to vote for
+    Vote(Uuid, usize),                    // Client UUID, the option to vote for
+    Predict(Uuid, [PlayerID; 3]),         // Client UUID, the predicted podium (1st, 2nd, 3rd)
+    Bet(Uuid, PlayerID, u32),             // Client UUID, the racer to win, how many points to wager
+    Emote(Uuid, Emote, Option<PlayerID>), // Client UUID, the reaction, who it's aimed at (if anyone)
+}
+
+type PendingHandshake = MidHandshake<ServerHandshake<TcpStream, NoCallback>>;
+
+enum WSConnectionState {
+    // the browser hasn't finished upgrading to a websocket yet
+    Handshaking {
+        handshake: PendingHandshake,
+        started_at: Instant,
+    },
+    Open(WebSocket<TcpStream>),
+    // we've sent or received a close frame and are waiting for it to finish up
+    Closing(WebSocket<TcpStream>),
+    Closed,
 }
 
 pub struct WSConnection {
-    socket: WebSocket<TcpStream>,
+    state: WSConnectionState,
     incoming_packets: VecDeque<WSServerBoundMessage>,
     outgoing_packets: VecDeque<Message>,
+    last_heard_from: Instant,
+    last_ping_sent: Instant,
 }
 
 impl WSConnection {
+    // never blocks: if the handshake can't finish right away we pick it back
+    // up every time we fetch incoming packets
     pub fn new(tcp_stream: TcpStream) -> Option<WSConnection> {
-        tcp_stream
-            .set_nonblocking(false)
-            .expect("expected to be able to set tcp nonblocking to false");
-        match accept(tcp_stream) {
-            Ok(socket) => {
-                socket
-                    .get_ref()
-                    .set_nonblocking(true)
-                    .expect("expected to be able to set tcp nonblocking to true");
-                return Some(WSConnection {
-                    socket,
-                    incoming_packets: VecDeque::new(),
-                    outgoing_packets: VecDeque::new(),
-                });
-            }
-            Err(err) => {
+        if let Err(err) = tcp_stream.set_nonblocking(true) {
+            println!("couldn't make an audience socket non-blocking, dropping it: {err}");
+            return None;
+        }
+
+        let now = Instant::now();
+        let state = match accept(tcp_stream) {
+            Ok(socket) => WSConnectionState::Open(socket),
+            Err(HandshakeError::Interrupted(handshake)) => WSConnectionState::Handshaking {
+                handshake,
+                started_at: now,
+            },
+            Err(HandshakeError::Failure(err)) => {
                 println!("something weird happened re web sockets; we don't really care though — error: {err}");
-                None
+                return None;
             }
-        }
+        };
+
+        Some(WSConnection {
+            state,
+            incoming_packets: VecDeque::new(),
+            outgoing_packets: VecDeque::new(),
+            last_heard_from: now,
+            last_ping_sent: now,
+        })
+    }
+
+    fn continue_handshake(&mut self) {
+        let state = std::mem::replace(&mut self.state, WSConnectionState::Closed);
+        self.state = match state {
+            WSConnectionState::Handshaking {
+                handshake,
+                started_at,
+            } => match handshake.handshake() {
+                Ok(socket) => {
+                    self.last_heard_from = Instant::now();
+                    WSConnectionState::Open(socket)
+                }
+                Err(HandshakeError::Interrupted(handshake)) => {
+                    if started_at.elapsed()
+                        > Duration::from_millis(GLOBAL_CONFIG.ws_handshake_timeout_ms)
+                    {
+                        println!("audience member took too long to shake hands, dropping them");
+                        WSConnectionState::Closed
+                    } else {
+                        WSConnectionState::Handshaking {
+                            handshake,
+                            started_at,
+                        }
+                    }
+                }
+                Err(HandshakeError::Failure(err)) => {
+                    println!("websocket handshake failed: {err}");
+                    WSConnectionState::Closed
+                }
+            },
+            other => other,
+        };
     }
 
+    // read every frame that's waiting for us
     pub fn fetch_incoming_packets(&mut self) {
-        if let Ok(msg) = self.socket.read_message() {
-            if msg.is_text() {
-                if let Ok(txt) = msg.to_text() {
-                    // this is where we handle shit
-                    let message_result: Result<WSServerBoundMessage, Error> =
-                        serde_json::from_str(txt);
-
-                    match message_result {
-                        Ok(server_bound_message) => {
-                            self.incoming_packets.push_back(server_bound_message)
+        if let WSConnectionState::Handshaking { .. } = self.state {
+            self.continue_handshake();
+        }
+
+        loop {
+            let socket = match &mut self.state {
+                WSConnectionState::Open(socket) | WSConnectionState::Closing(socket) => socket,
+                _ => return,
+            };
+
+            match socket.read_message() {
+                Ok(msg) => {
+                    self.last_heard_from = Instant::now();
+                    match msg {
+                        Message::Text(txt) => {
+                            let message_result: Result<WSServerBoundMessage, Error> =
+                                serde_json::from_str(&txt);
+
+                            match message_result {
+                                Ok(server_bound_message) => {
+                                    self.incoming_packets.push_back(server_bound_message)
+                                }
+                                Err(err) => {
+                                    println!("got an error! we're going to do nothing about this!");
+                                    println!("{}", err);
+                                }
+                            }
                         }
-                        Err(err) => {
-                            println!("got an error! we're going to do nothing about this!");
-                            println!("{}", err);
+                        // tungstenite has already queued our half of the close; keep
+                        // reading until it tells us the connection is done
+                        Message::Close(_) => {
+                            if let WSConnectionState::Open(socket) =
+                                std::mem::replace(&mut self.state, WSConnectionState::Closed)
+                            {
+                                self.state = WSConnectionState::Closing(socket);
+                            }
                         }
+                        // tungstenite answers pings for us, and pongs only
+                        // matter for keeping last_heard_from fresh
+                        Message::Ping(_) | Message::Pong(_) | Message::Binary(_) => {}
                     }
                 }
+                Err(WSError::Io(err)) if err.kind() == ErrorKind::WouldBlock => return,
+                Err(WSError::ConnectionClosed) | Err(WSError::AlreadyClosed) => {
+                    self.state = WSConnectionState::Closed;
+                    return;
+                }
+                Err(err) => {
+                    println!("audience socket errored out, dropping it: {err}");
+                    self.state = WSConnectionState::Closed;
+                    return;
+                }
             }
         }
     }
@@ -118,24 +286,87 @@ impl WSConnection {
         self.push_outgoing(message);
     }
 
-    // send packets on this connection until exhausted
+    // politely tell the browser we're done with it
+    pub fn close(&mut self, code: CloseCode, reason: &'static str) {
+        if let WSConnectionState::Open(mut socket) =
+            std::mem::replace(&mut self.state, WSConnectionState::Closed)
+        {
+            let frame = CloseFrame {
+                code,
+                reason: reason.into(),
+            };
+            self.state = match socket.close(Some(frame)) {
+                Ok(()) => WSConnectionState::Closing(socket),
+                Err(WSError::Io(err)) if err.kind() == ErrorKind::WouldBlock => {
+                    WSConnectionState::Closing(socket)
+                }
+                Err(_) => WSConnectionState::Closed,
+            };
+        }
+    }
+
+    // ping the browser every so often, and give up on it if it stops answering
+    fn keep_alive(&mut self) {
+        let now = Instant::now();
+        let idle =
+            now - self.last_heard_from > Duration::from_millis(GLOBAL_CONFIG.ws_idle_timeout_ms);
+        let ping_due =
+            now - self.last_ping_sent > Duration::from_millis(GLOBAL_CONFIG.ws_ping_interval_ms);
+
+        match self.state {
+            WSConnectionState::Open(_) if idle => {
+                println!("audience member went quiet, hanging up on them");
+                self.close(CloseCode::Away, "idle timeout");
+            }
+            WSConnectionState::Open(_) if ping_due => {
+                self.last_ping_sent = now;
+                self.outgoing_packets.push_front(Message::Ping(vec![]));
+            }
+            // don't wait forever for the other side to finish closing
+            WSConnectionState::Closing(_) if idle => self.state = WSConnectionState::Closed,
+            _ => {}
+        }
+    }
+
+    // send packets on this connection until exhausted; returns whether the
+    // connection is still worth keeping around
     pub fn sync_outgoing(&mut self) -> bool {
-        let mut could_send_messages = true;
-        while let Some(msg) = self.outgoing_packets.pop_front() {
-            if self.socket.can_write() {
-                let result = self.socket.write_message(msg);
-                if result.is_err() {
-                    println!(
-                        "failed to write to socket because of {}",
-                        result.unwrap_err()
-                    );
-                    could_send_messages = false;
+        self.keep_alive();
+
+        match &mut self.state {
+            WSConnectionState::Handshaking { .. } => true,
+            WSConnectionState::Open(socket) => {
+                while let Some(msg) = self.outgoing_packets.pop_front() {
+                    match socket.write_message(msg) {
+                        Ok(()) => {}
+                        // the message is buffered inside tungstenite and will
+                        // get flushed next time we read or write
+                        Err(WSError::Io(err)) if err.kind() == ErrorKind::WouldBlock => {}
+                        Err(WSError::SendQueueFull(msg)) => {
+                            self.outgoing_packets.push_front(msg);
+                            break;
+                        }
+                        Err(err) => {
+                            println!("failed to write to socket because of {}", err);
+                            self.state = WSConnectionState::Closed;
+                            return false;
+                        }
+                    }
+                }
+                true
+            }
+            WSConnectionState::Closing(socket) => {
+                self.outgoing_packets.clear();
+                match socket.write_pending() {
+                    Ok(()) => true,
+                    Err(WSError::Io(err)) if err.kind() == ErrorKind::WouldBlock => true,
+                    Err(_) => {
+                        self.state = WSConnectionState::Closed;
+                        false
+                    }
                 }
-            } else {
-                println!("couldn't write? :thinking_emoji");
-                could_send_messages = false;
             }
+            WSConnectionState::Closed => false,
         }
-        could_send_messages
     }
 }
@@ -7,6 +7,8 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+use crate::networking::ws::TrackRect;
+use crate::player::lap_info::LapNumber;
 use crate::GLOBAL_CONFIG;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -20,7 +22,7 @@ impl Default for PlayerChoices {
     fn default() -> Self {
         Self {
             chair: Chair::Swivel,
-            map: Track::Track,
+            map: Track::default(),
             ready: false,
         }
     }
@@ -43,7 +45,13 @@ lazy_static! {
         (Stat::CarSpin, GLOBAL_CONFIG.car_spin),
         (Stat::MaxCarSpeed, GLOBAL_CONFIG.max_car_speed),
         (Stat::MaxCarSpin, GLOBAL_CONFIG.max_car_spin),
-        (Stat::Mass, GLOBAL_CONFIG.gravity_coefficient),
+        (Stat::CarGrip, GLOBAL_CONFIG.car_grip),
+        (Stat::DriftGrip, GLOBAL_CONFIG.drift_grip),
+        (Stat::DriftChargeRate, GLOBAL_CONFIG.drift_charge_rate),
+        (Stat::DriftBoost, GLOBAL_CONFIG.drift_boost),
+        // only matters for bumping into each other; the recliner is heavier
+        // than this, and the beanbag and folding chairs lighter
+        (Stat::Mass, 10.0),
         (Stat::TerrainBounciness, GLOBAL_CONFIG.wall_bounciness),
         (Stat::PlayerBounciness, GLOBAL_CONFIG.player_bounciness),
     ]
@@ -63,6 +71,10 @@ pub enum Stat {
     CarSpin,
     MaxCarSpeed,
     MaxCarSpin,
+    CarGrip,
+    DriftGrip,
+    DriftChargeRate,
+    DriftBoost,
     Mass,
     PlayerBounciness,
     TerrainBounciness,
@@ -108,6 +120,8 @@ impl Chair {
                 }
 
                 Stat::DragCoefficient => GLOBAL_CONFIG.drag_coefficient * 0.02,
+                // Born to spin, so drifts charge up quick
+                Stat::DriftChargeRate => GLOBAL_CONFIG.drift_charge_rate * 1.5,
                 _ => *Chair::default_stats().get(stat_name).unwrap(),
             },
             Chair::Recliner => match stat_name {
@@ -126,6 +140,9 @@ impl Chair {
                 // We have a bit of braking power, though
                 Stat::CarBrake => GLOBAL_CONFIG.car_brake * 10.0,
                 Stat::Mass => 50.0,
+                // Hard to throw sideways, but a lot of chair once it gets going
+                Stat::DriftGrip => GLOBAL_CONFIG.drift_grip * 2.0,
+                Stat::DriftBoost => GLOBAL_CONFIG.drift_boost * 1.3,
                 _ => *Chair::default_stats().get(stat_name).unwrap(),
             },
             Chair::Ergonomic => match stat_name {
@@ -142,6 +159,9 @@ impl Chair {
                 Stat::DragCoefficient => GLOBAL_CONFIG.drag_coefficient * 1.5,
                 // And we have a decent break
                 Stat::CarBrake => GLOBAL_CONFIG.car_brake * 15.0,
+                // Sticks to the floor, which makes drifting a bit of work
+                Stat::CarGrip => GLOBAL_CONFIG.car_grip * 2.0,
+                Stat::DriftChargeRate => GLOBAL_CONFIG.drift_charge_rate * 0.8,
                 _ => *Chair::default_stats().get(stat_name).unwrap(),
             },
             Chair::Beanbag => match stat_name {
@@ -155,6 +175,9 @@ impl Chair {
                     GLOBAL_CONFIG.rolling_resistance_coefficient * 0.2
                 }
                 Stat::Mass => 1.0,
+                // Goes every which way when drifting
+                Stat::DriftGrip => GLOBAL_CONFIG.drift_grip * 0.5,
+                Stat::DriftBoost => GLOBAL_CONFIG.drift_boost * 0.8,
                 _ => *Chair::default_stats().get(stat_name).unwrap(),
             },
             Chair::Folding => match stat_name {
@@ -166,6 +189,9 @@ impl Chair {
                 Stat::DragCoefficient => GLOBAL_CONFIG.drag_coefficient * 0.001,
                 Stat::RollingResistanceCoefficient => GLOBAL_CONFIG.rolling_resistance_coefficient,
                 Stat::Mass => 1.0,
+                // Slides all over the place at the best of times
+                Stat::CarGrip => GLOBAL_CONFIG.car_grip * 0.5,
+                Stat::DriftBoost => GLOBAL_CONFIG.drift_boost * 1.2,
                 _ => *Chair::default_stats().get(stat_name).unwrap(),
             },
         }
@@ -201,16 +227,45 @@ pub enum CameraType {
     FaceVelocity,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
-pub enum Track {
-    Track,
+// A racetrack, named after its .glb file in the tracks folder
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
+pub struct Track {
+    pub name: String,
+}
+
+impl Track {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+        }
+    }
+}
+
+impl Default for Track {
+    // the track we've always shipped with
+    fn default() -> Self {
+        Track::new("track")
+    }
 }
 
 impl fmt::Display for Track {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let printable = match *self {
-            Track::Track => "track",
-        };
-        write!(f, "{}", printable)
+        write!(f, "{}", self.name)
     }
 }
+
+// What the lobby needs to know about a track to let players vote for it
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct TrackInfo {
+    pub track: Track,
+    pub display_name: String,
+}
+
+// Everything about a track that isn't geometry, as read out of the track's
+// glb; anything the track doesn't specify falls back to our config
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TrackMetadata {
+    pub display_name: String,
+    pub laps: LapNumber,
+    pub minimap_bounds: TrackRect, // the part of the world the minimap covers
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::time::Duration;
 
 pub type LapNumber = u8;
 pub type CheckpointID = u64;
@@ -10,7 +11,17 @@ pub type Placement = u8;
 pub struct LapInformation {
     pub lap: LapNumber,
     pub zone: ZoneID,
-    pub last_checkpoint: CheckpointID,
+    pub last_checkpoint: Option<CheckpointID>, // None until we've been through one
+    pub lap_distance: f64, // how far along the track's centerline we are this lap
+}
+
+// How long a lap took, and how far into it we were each time we got into
+// the next zone, ending with the finish line
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LapTime {
+    pub lap: LapNumber,
+    pub time: Duration,
+    pub splits: Vec<Duration>,
 }
 
 impl LapInformation {
@@ -18,7 +29,8 @@ impl LapInformation {
         LapInformation {
             lap: 1,
             zone: 0,
-            last_checkpoint: 0,
+            last_checkpoint: None,
+            lap_distance: 0.0,
         }
     }
 }
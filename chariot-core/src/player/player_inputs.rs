@@ -6,6 +6,7 @@ type Modifier = f32;
 pub enum InputEvent {
     Engine(EngineStatus),
     Rotation(RotationStatus),
+    Drift(bool), // Holding down (or letting go of) the drift button
 }
 
 #[derive(Copy, Clone, Serialize, Deserialize, Debug)]
@@ -28,4 +29,5 @@ pub enum RotationStatus {
 pub struct PlayerInputs {
     pub engine_status: EngineStatus,
     pub rotation_status: RotationStatus,
+    pub drifting: bool,
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerUp {
+    // Beneficial
+    Coffee, // A short burst of speed
+
+    // Detrimental
+    ShockEm,      // Fires a bolt that homes in on whoever is one place ahead of you
+    WetFloorSign, // Drops a sign behind you that sends whoever runs into it spinning
+}
+
+impl PowerUp {
+    pub fn get_name(&self) -> &str {
+        match self {
+            PowerUp::Coffee => "coffee",
+            PowerUp::ShockEm => "shock 'em",
+            PowerUp::WetFloorSign => "wet floor sign",
+        }
+    }
+}
@@ -3,6 +3,8 @@ use rand::seq::SliceRandom;
 use rand::thread_rng;
 use serde::{Deserialize, Serialize};
 
+use crate::player::PlayerID;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct QuestionData {
     pub prompt: String,
@@ -12,6 +14,17 @@ pub struct QuestionData {
 pub struct QuestionOption {
     pub label: String,
     pub action: AudienceAction,
+    pub target: AudienceTarget,
+}
+
+// Who an audience action gets applied to; the server resolves this to a set of
+// players when the vote closes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudienceTarget {
+    Everyone,
+    Leader,           // Whoever is in first place when the vote closes
+    LastPlace,        // Whoever is in last place when the vote closes
+    Player(PlayerID), // One specific racer
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -33,6 +46,8 @@ pub enum AudienceAction {
     SpeedBalanceBoost, // Everyone except the first-place player gets 1.5x speed
 
     // Physics stats
+    SlowDown,                  // Max speed is halved
+    SpeedBoost,                // Max speed and acceleration are boosted
     DoubleMaxSpeed,            // Players can go up to double starting maximum speed
     SuperAccelerator,          // Players accelerate 3 times as fast
     SuperSpin,                 // Players spin 5 times as fast
@@ -46,6 +61,7 @@ pub enum AudienceAction {
     ShufflePlayerPositions, // All players' positions are switched
     RewindLapCounter,       // Change everyone's lap counter back 1 lap
     Backwards,              // Players instantly rotate 180 degrees and have their speed inverted
+    WetFloor,               // A wet floor sign appears right in front of each player
 }
 
 impl AudienceAction {
@@ -63,6 +79,8 @@ impl AudienceAction {
             AudienceAction::TurnOnlyWhenNotMoving => "You can only turn when you stop moving!",
             AudienceAction::ShoppingCart => "Don't you just love how shopping carts drift?",
             AudienceAction::SpeedBalanceBoost => "Everyone but first place: FULL SPEED AHEAD",
+            AudienceAction::SlowDown => "Slow down there, speedy!",
+            AudienceAction::SpeedBoost => "A little push from the crowd!",
             AudienceAction::DoubleMaxSpeed => "You can go faster now :O",
             AudienceAction::SuperAccelerator => "You can accelerate faster now B)",
             AudienceAction::SuperSpin => "I'll try spinning, that's a cool trick!",
@@ -74,6 +92,7 @@ impl AudienceAction {
             AudienceAction::ShufflePlayerPositions => "Oops, we seem to have misplaced you all!",
             AudienceAction::RewindLapCounter => "Everyone has been set back one lap!",
             AudienceAction::Backwards => "Whoops! Drive backwards now!",
+            AudienceAction::WetFloor => "Caution: wet floor!",
         }
     }
 }
@@ -86,10 +105,12 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Left".to_string(),
                     action: AudienceAction::NoLeft,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Right".to_string(),
                     action: AudienceAction::NoRight,
+                    target: AudienceTarget::Everyone,
                 },
             ],
         },
@@ -99,14 +120,17 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Invert controls".to_string(),
                     action: AudienceAction::InvertControls,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Accelerate no matter what".to_string(),
                     action: AudienceAction::AutoAccelerate,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Only allow turns when not moving".to_string(),
                     action: AudienceAction::TurnOnlyWhenNotMoving,
+                    target: AudienceTarget::Everyone,
                 },
             ],
         },
@@ -116,10 +140,12 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Moon gravity (less gravity)".to_string(),
                     action: AudienceAction::MoonGravity,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Ice rink (no friction)".to_string(),
                     action: AudienceAction::IceRink,
+                    target: AudienceTarget::Everyone,
                 },
             ],
         },
@@ -129,10 +155,12 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Swap first and last place".to_string(),
                     action: AudienceAction::SwapFirstAndLast,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Give a speed boost to everyone except first place".to_string(),
                     action: AudienceAction::SpeedBalanceBoost,
+                    target: AudienceTarget::Everyone,
                 },
             ],
         },
@@ -142,10 +170,12 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Players".to_string(),
                     action: AudienceAction::ExplosivePlayerCollisions,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Terrain".to_string(),
                     action: AudienceAction::SuperBouncyObjects,
+                    target: AudienceTarget::Everyone,
                 },
             ],
         },
@@ -155,10 +185,12 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Higher max speed for everyone".to_string(),
                     action: AudienceAction::DoubleMaxSpeed,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Faster acceleration for everyone".to_string(),
                     action: AudienceAction::SuperAccelerator,
+                    target: AudienceTarget::Everyone,
                 },
             ],
         },
@@ -168,10 +200,12 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Super spin: turn super fast".to_string(),
                     action: AudienceAction::SuperSpin,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Shopping cart mode: perpetual drift to the right".to_string(),
                     action: AudienceAction::ShoppingCart,
+                    target: AudienceTarget::Everyone,
                 },
             ],
         },
@@ -181,10 +215,12 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Yes!".to_string(),
                     action: AudienceAction::ShufflePlayerPositions,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "No!".to_string(),
                     action: AudienceAction::Null,
+                    target: AudienceTarget::Everyone,
                 },
             ],
         },
@@ -194,13 +230,56 @@ fn get_shuffled_questions() -> Vec<QuestionData> {
                 QuestionOption {
                     label: "Set everybody back one lap!".to_string(),
                     action: AudienceAction::RewindLapCounter,
+                    target: AudienceTarget::Everyone,
                 },
                 QuestionOption {
                     label: "Flip everyone backwards".to_string(),
                     action: AudienceAction::Backwards,
+                    target: AudienceTarget::Everyone,
+                },
+            ],
+        },
+        QuestionData {
+            prompt: "Someone is getting too comfortable. Who gets the audience's attention?"
+                .to_string(),
+            options: vec![
+                QuestionOption {
+                    label: "Slow down the leader".to_string(),
+                    action: AudienceAction::SlowDown,
+                    target: AudienceTarget::Leader,
+                },
+                QuestionOption {
+                    label: "Boost whoever is in last".to_string(),
+                    action: AudienceAction::SpeedBoost,
+                    target: AudienceTarget::LastPlace,
                 },
             ],
         },
+        QuestionData {
+            prompt: "The janitor is on the way. Where should they mop?".to_string(),
+            options: vec![
+                QuestionOption {
+                    label: "In front of the leader".to_string(),
+                    action: AudienceAction::WetFloor,
+                    target: AudienceTarget::Leader,
+                },
+                QuestionOption {
+                    label: "In front of everyone".to_string(),
+                    action: AudienceAction::WetFloor,
+                    target: AudienceTarget::Everyone,
+                },
+            ],
+        },
+        QuestionData {
+            prompt: "Pick a victim: whose controls should we invert?".to_string(),
+            options: (0..4)
+                .map(|player_id| QuestionOption {
+                    label: format!("Player {}", player_id + 1),
+                    action: AudienceAction::InvertControls,
+                    target: AudienceTarget::Player(player_id),
+                })
+                .collect(),
+        },
     ];
 
     questions.shuffle(&mut thread_rng());
@@ -8,8 +8,12 @@ pub struct Settings {
     pub port: String,
     pub server_address: String,
     pub server_tick_ms: u64,
+    pub physics_substeps: u32,
     pub player_amount: usize,
     pub ws_server_port: String,
+    pub ws_handshake_timeout_ms: u64,
+    pub ws_ping_interval_ms: u64,
+    pub ws_idle_timeout_ms: u64,
 
     pub start_fullscreen: bool,
 
@@ -21,6 +25,9 @@ pub struct Settings {
     pub powerup_cooldown_time: u64,
     pub volume: f32,
     pub enable_particle_effects: bool,
+    pub respawn_stuck_ms: u64,
+    pub respawn_ghost_ms: u64,
+    pub respawn_out_of_bounds_distance: f64,
 
     // Physics
     pub gravity_coefficient: f64,
@@ -36,6 +43,11 @@ pub struct Settings {
     pub max_car_speed: f64,
     pub max_car_spin: f64,
 
+    pub car_grip: f64,
+    pub drift_grip: f64,
+    pub drift_charge_rate: f64,
+    pub drift_boost: f64,
+
     pub wall_bounciness: f64,
     pub player_bounciness: f64,
 
@@ -43,6 +55,14 @@ pub struct Settings {
 
     // Voting
     pub audience_vote_time_ms: u64,
+    pub audience_position_sync_ms: u64,
+    pub audience_emote_cooldown_ms: u64,
+    pub audience_emote_batch_ms: u64,
+
+    // Predictions
+    pub audience_starting_points: u32,
+    pub prediction_winner_points: u32,
+    pub prediction_podium_points: u32,
 }
 
 impl Settings {
@@ -52,7 +72,16 @@ impl Settings {
             .set_default("port", "24247")?
             .set_default("server_address", "127.0.0.1")?
             .set_default("ws_server_port", "0.0.0.0:2334")?
+            // audience sockets that take longer than this to upgrade get dropped
+            .set_default("ws_handshake_timeout_ms", 5000)?
+            // how often we ping audience members, and how long they can go
+            // without saying anything (pongs count) before we hang up
+            .set_default("ws_ping_interval_ms", 5000)?
+            .set_default("ws_idle_timeout_ms", 15000)?
             .set_default("server_tick_ms", 30)?
+            // how many fixed physics steps make up each server tick; more is
+            // smoother and harder to tunnel through things, but costs more
+            .set_default("physics_substeps", 4)?
             .set_default("player_amount", 4)?
             // display settings
             .set_default("start_fullscreen", true)?
@@ -63,12 +92,21 @@ impl Settings {
             .set_default("powerup_cooldown_time", 10)?
             .set_default("volume", 1.0)?
             .set_default("enable_particle_effects", true)?
+            // how long a player can sit stopped against a wall before we put
+            // them back at their last checkpoint
+            .set_default("respawn_stuck_ms", 3000)?
+            // how long after a respawn a player drives straight through everyone else
+            .set_default("respawn_ghost_ms", 2000)?
+            // how far outside the track (or below its lowest point) a player
+            // can get before we decide they've fallen off it
+            .set_default("respawn_out_of_bounds_distance", 20.0)?
             // physics
+            // Everything here is in world units and seconds, so it doesn't
+            // matter how often the physics actually runs.
             // `gravity_coefficient` is acceleration due to gravity: this is
-            // little g (whose IRL value is 9.81 meters per second squared, but
-            // we are not operating in those units so this is a placeholder
-            // value for now).
-            .set_default("gravity_coefficient", 0.01)?
+            // little g (whose IRL value is 9.81 meters per second squared;
+            // ours is a little floatier).
+            .set_default("gravity_coefficient", 11.1)?
             // We model air resistance with a (very) simplified model of
             // `drag_coefficient` times velocity squared. Since drag is
             // quadratic and friction is linear, this coefficient should be much
@@ -77,29 +115,59 @@ impl Settings {
             // that drag dominates at higher speeds.
             .set_default("drag_coefficient", 0.003)?
             // Rolling resistance is modelled as being linearly proportional to
-            // velocity; see notes about the drag coefficient for information
-            // about their relative magnitudes.
-            .set_default("rolling_resistance_coefficient", 0.08)?
+            // velocity, in units of 1 / second; see notes about the drag
+            // coefficient for information about their relative magnitudes.
+            .set_default("rolling_resistance_coefficient", 2.67)?
             // This doesn't have a real-world equivalent, but we might call it
-            // the rotational analogue of friction: each time step in free
-            // rotation, what proportion of angular velocity should be
-            // conserved?
+            // the rotational analogue of friction: each 30ms in free rotation,
+            // what proportion of angular velocity should be conserved?
             .set_default("rotation_reduction_coefficient", 0.40)?
-            // Coefficient to control how forceful player-controlled acceleration is
-            .set_default("car_accelerator", 0.8 / 15.0)?
+            // Coefficient to control how forceful player-controlled
+            // acceleration is (units per second squared)
+            .set_default("car_accelerator", 59.3)?
             // Coefficient to control how forceful player-controlled braking is
-            .set_default("car_brake", 0.005)?
-            // Coefficient to control how fast the player can spin
-            .set_default("car_spin", 0.03)?
-            .set_default("max_car_speed", 0.5)?
-            .set_default("max_car_spin", 0.1)?
+            .set_default("car_brake", 5.56)?
+            // Coefficient to control how fast the player can spin (radians
+            // per second squared)
+            .set_default("car_spin", 33.3)?
+            // units per second
+            .set_default("max_car_speed", 16.7)?
+            // radians per second
+            .set_default("max_car_spin", 3.33)?
+            // How quickly we stop sliding sideways, in 1 / second; the higher
+            // this is, the more we go where we're facing
+            .set_default("car_grip", 2.0)?
+            // How much of that grip we keep while drifting
+            .set_default("drift_grip", 0.25)?
+            // How much of the drift meter fills each second of drifting
+            // completely sideways (less at shallower angles)
+            .set_default("drift_charge_rate", 0.8)?
+            // How much faster a full drift meter lets us go once we let go
+            // (0.5 => 50% faster top speed and acceleration)
+            .set_default("drift_boost", 0.5)?
             // How hard we should bounce off the walls (1.0 = as fast as we were initially going)
-            .set_default("wall_bounciness", 3.0)?
-            // How hard we should bounce off other players (1.0 = real-world physically accurate)
-            .set_default("player_bounciness", 3.0)?
-            // How much slower should you go when off-track? (0.20 => 80% of on-track speed when off)
+            .set_default("wall_bounciness", 1.0)?
+            // How hard we should bounce off other players: how much of the
+            // speed we hit each other with we get back (1.0 = perfectly
+            // elastic, 0.0 = we just stop dead)
+            .set_default("player_bounciness", 0.8)?
+            // How much slower should you go when off-track? (0.20 => lose 20% of your speed every 30ms when off)
             .set_default("off_track_speed_penalty", 0.20)?
             .set_default("audience_vote_time_ms", 30000)?
+            // How often the audience gets told where every racer is; this goes
+            // out to every browser so it shouldn't be every tick
+            .set_default("audience_position_sync_ms", 150)?
+            // Each audience member can only send one emote this often...
+            .set_default("audience_emote_cooldown_ms", 750)?
+            // ...and players get them in batches, so a big audience doesn't flood the HUD
+            .set_default("audience_emote_batch_ms", 500)?
+            // predictions
+            // How many points every audience member gets to bet with when they first connect
+            .set_default("audience_starting_points", 1000)?
+            // Awarded for correctly predicting the winner
+            .set_default("prediction_winner_points", 300)?
+            // Awarded for each other racer predicted in exactly the right podium spot
+            .set_default("prediction_podium_points", 100)?
             .add_source(File::with_name("config.yaml").required(false))
             .build()?;
 
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+pub type WorldEntityID = u32;
+
+// Things the server drops into the world mid-race; every client renders them,
+// and whoever drives into one gets hit by its effect
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorldEntityKind {
+    WetFloorSign, // Sends whoever runs into it spinning
+    ShockEmBolt,  // Homes in on a racer and stuns whoever it hits
+}
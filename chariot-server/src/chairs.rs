@@ -17,8 +17,9 @@ use crate::progress::PlayerProgress;
 // We could implement something to load mass and size from a file or whatever,
 // but it's probably just fine to hard-code them in here
 
-// These numbers are completely random guesses btw
-fn get_starting_position_from_player_number(player_number: PlayerID) -> DVec3 {
+// These numbers are completely random guesses btw; tracks with a start grid
+// don't need them
+pub fn get_starting_position_from_player_number(player_number: PlayerID) -> DVec3 {
     return DVec3::new(21.5 + 2.0 * (1.5 - player_number as f64), 0.32, 65.0);
 }
 
@@ -27,11 +28,16 @@ pub fn get_player_start_physics_properties(chair: &Chair, player_number: PlayerI
     return PlayerEntity {
         velocity: DVec3::ZERO,
         angular_velocity: 0.0,
+        drift_charge: 0.0,
+        surface: None,
+        on_boost_pad: false,
+        stuck_time: 0.0,
         size: chair.scale(),
         bounding_box: BoundingBox::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0), // will be made correct on the first physics tick
         player_inputs: PlayerInputs {
             engine_status: EngineStatus::Neutral,
             rotation_status: RotationStatus::NotInSpin,
+            drifting: false,
         },
         entity_location: EntityLocation {
             position: get_starting_position_from_player_number(player_number),
@@ -47,5 +53,6 @@ pub fn get_player_start_physics_properties(chair: &Chair, player_number: PlayerI
         game_start_time: Instant::now(),
         placement_data: PlayerProgress::PreGame,
         cached_place: None,
+        lap_timer: Default::default(),
     };
 }
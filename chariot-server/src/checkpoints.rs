@@ -3,6 +3,7 @@ use std::time::Instant;
 use crate::physics::bounding_box::BoundingBox;
 use crate::physics::{player_entity::PlayerEntity, trigger_entity::TriggerEntity};
 use crate::progress::PlayerProgress;
+use chariot_core::entity_location::EntityLocation;
 use chariot_core::player::lap_info::*;
 use chariot_core::GLOBAL_CONFIG;
 use glam::DVec3;
@@ -11,11 +12,18 @@ use glam::DVec3;
 pub struct Checkpoint {
     pub id: CheckpointID,
     pub bounds: BoundingBox,
+    // where (and facing which way) players who get stuck after this
+    // checkpoint are put back
+    pub respawn: EntityLocation,
 }
 
 impl Checkpoint {
-    pub fn new(id: CheckpointID, bounds: BoundingBox) -> Self {
-        Self { id, bounds }
+    pub fn new(id: CheckpointID, bounds: BoundingBox, respawn: EntityLocation) -> Self {
+        Self {
+            id,
+            bounds,
+            respawn,
+        }
     }
 }
 
@@ -30,7 +38,7 @@ impl TriggerEntity for Checkpoint {
 
     fn trigger(&mut self, player: &mut PlayerEntity) {
         if let PlayerProgress::Racing { lap_info } = &mut player.placement_data {
-            lap_info.last_checkpoint = self.id;
+            lap_info.last_checkpoint = Some(self.id);
         }
     }
 }
@@ -61,6 +69,9 @@ impl TriggerEntity for Zone {
         if let PlayerProgress::Racing { lap_info } = &mut player.placement_data {
             if (lap_info.zone + 1) == self.id {
                 lap_info.zone = self.id;
+                player
+                    .lap_timer
+                    .split(Instant::now() - player.game_start_time);
                 println!("Player now in zone {}", self.id);
             }
         }
@@ -70,18 +81,32 @@ impl TriggerEntity for Zone {
 #[derive(Clone, Copy)]
 pub struct FinishLine {
     last_zone: ZoneID,
+    laps: LapNumber,
     pub bounds: BoundingBox,
 }
 
 impl FinishLine {
     pub fn new(bounds: BoundingBox, last_zone: ZoneID) -> Self {
-        Self { last_zone, bounds }
+        Self {
+            last_zone,
+            laps: GLOBAL_CONFIG.number_laps,
+            bounds,
+        }
+    }
+
+    pub fn last_zone(&self) -> ZoneID {
+        self.last_zone
     }
 
     pub fn set_last_zone(&mut self, last_zone: ZoneID) -> Self {
         self.last_zone = last_zone;
         *self
     }
+
+    pub fn set_laps(&mut self, laps: LapNumber) -> Self {
+        self.laps = laps;
+        *self
+    }
 }
 
 impl TriggerEntity for FinishLine {
@@ -97,8 +122,10 @@ impl TriggerEntity for FinishLine {
         // Player is only allowed to advance if they are on the track's last zone
         if let PlayerProgress::Racing { lap_info } = &mut player.placement_data {
             if lap_info.zone == self.last_zone {
-                if lap_info.lap == GLOBAL_CONFIG.number_laps {
-                    let finish_time = Instant::now() - player.game_start_time;
+                let race_time = Instant::now() - player.game_start_time;
+                player.lap_timer.complete_lap(lap_info.lap, race_time);
+                if lap_info.lap == self.laps {
+                    let finish_time = race_time;
                     println!("Player has finished in {:?}!", &finish_time);
                     player.placement_data = PlayerProgress::Finished { finish_time };
                 } else {
@@ -0,0 +1,84 @@
+use chariot_core::player::lap_info::ZoneID;
+use glam::DVec3;
+
+use crate::checkpoints::{FinishLine, Zone};
+
+// A point along the middle of the track
+#[derive(Clone, Copy, Debug)]
+struct Waypoint {
+    // you're heading towards this point until you're in this zone
+    zone: ZoneID,
+    position: DVec3,
+    // how far around the lap this point is
+    distance: f64,
+}
+
+// A rough line around the middle of the track, going straight from the
+// finish line through the middle of each zone in order and back to the
+// finish line; how far along it someone is is how far around the lap they are
+#[derive(Clone, Debug)]
+pub struct Centerline {
+    waypoints: Vec<Waypoint>,
+}
+
+// only looking from above, so hills don't make the track any longer
+fn flat_distance(a: DVec3, b: DVec3) -> f64 {
+    DVec3::new(a.x - b.x, 0.0, a.z - b.z).length()
+}
+
+impl Centerline {
+    pub fn new(finish_line: &FinishLine, zones: &[Zone]) -> Self {
+        let mut zones = zones.to_vec();
+        zones.sort_by_key(|zone| zone.id);
+
+        let start = (0, finish_line.bounds.pos());
+        let finish = (ZoneID::MAX, finish_line.bounds.pos());
+        let points = std::iter::once(start)
+            .chain(
+                zones
+                    .iter()
+                    .filter(|zone| zone.id > 0)
+                    .map(|zone| (zone.id, zone.bounds.pos())),
+            )
+            .chain(std::iter::once(finish));
+
+        let mut waypoints: Vec<Waypoint> = vec![];
+        for (zone, position) in points {
+            let distance = match waypoints.last() {
+                Some(previous) => previous.distance + flat_distance(previous.position, position),
+                None => 0.0,
+            };
+            waypoints.push(Waypoint {
+                zone,
+                position,
+                distance,
+            });
+        }
+
+        Self { waypoints }
+    }
+
+    // how far it is around the whole lap
+    pub fn lap_length(&self) -> f64 {
+        self.waypoints
+            .last()
+            .map(|waypoint| waypoint.distance)
+            .unwrap_or(0.0)
+    }
+
+    // How far around the lap someone at `position` in `zone` is: however far
+    // along the waypoint they're heading for is, less how far off it they
+    // still are, but never back past the one they've already reached
+    pub fn distance_along(&self, zone: ZoneID, position: DVec3) -> f64 {
+        let target = self
+            .waypoints
+            .iter()
+            .position(|waypoint| waypoint.zone > zone)
+            .unwrap_or(self.waypoints.len() - 1)
+            .max(1);
+        let (previous, target) = (self.waypoints[target - 1], self.waypoints[target]);
+
+        let to_go = flat_distance(position, target.position);
+        (target.distance - to_go).clamp(previous.distance, target.distance)
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chariot_core::networking::ws::Emote;
+use chariot_core::networking::{ClientBoundPacket, Uuid};
+use chariot_core::player::PlayerID;
+use chariot_core::GLOBAL_CONFIG;
+
+use crate::game::GameServer;
+
+use super::phase::GamePhase;
+
+// Collects audience reactions between batches so players see "boo! x12"
+// instead of twelve separate boos
+pub struct EmoteAggregator {
+    last_emote_from: HashMap<Uuid, Instant>,
+    pending: HashMap<(Emote, Option<PlayerID>), u32>,
+    last_batch_sent: Instant,
+}
+
+impl EmoteAggregator {
+    pub fn new() -> Self {
+        Self {
+            last_emote_from: HashMap::new(),
+            pending: HashMap::new(),
+            last_batch_sent: Instant::now(),
+        }
+    }
+
+    // returns false if this audience member is sending emotes too quickly
+    pub fn record(&mut self, id: Uuid, emote: Emote, target: Option<PlayerID>) -> bool {
+        let now = Instant::now();
+        let cooldown = Duration::from_millis(GLOBAL_CONFIG.audience_emote_cooldown_ms);
+        // anyone whose cooldown is up doesn't need remembering any more
+        self.last_emote_from
+            .retain(|_, &mut last_emote| now - last_emote < cooldown);
+        if self.last_emote_from.contains_key(&id) {
+            return false;
+        }
+
+        self.last_emote_from.insert(id, now);
+        *self.pending.entry((emote, target)).or_insert(0) += 1;
+        true
+    }
+
+    // this audience member's connection closed
+    pub fn forget(&mut self, id: &Uuid) {
+        self.last_emote_from.remove(id);
+    }
+
+    // everything that's come in since the last batch, if it's time for another one
+    pub fn take_batch(&mut self) -> Option<Vec<(Emote, Option<PlayerID>, u32)>> {
+        let now = Instant::now();
+        if self.pending.is_empty()
+            || now - self.last_batch_sent
+                < Duration::from_millis(GLOBAL_CONFIG.audience_emote_batch_ms)
+        {
+            return None;
+        }
+
+        self.last_batch_sent = now;
+        Some(
+            self.pending
+                .drain()
+                .map(|((emote, target), count)| (emote, target, count))
+                .collect(),
+        )
+    }
+}
+
+impl GameServer {
+    // emotes only make sense while there's a race to react to
+    pub fn emotes_are_open(&self) -> bool {
+        matches!(
+            self.game_state.phase,
+            GamePhase::CountingDownToGameStart(_) | GamePhase::PlayingGame { .. }
+        )
+    }
+
+    pub fn sync_audience_emotes(&mut self) {
+        if !self.emotes_are_open() {
+            return;
+        }
+
+        if let Some(batch) = self.audience_emotes.take_batch() {
+            for connection in &mut self.connections {
+                connection.push_outgoing(ClientBoundPacket::AudienceEmotes(batch.clone()));
+            }
+        }
+    }
+}
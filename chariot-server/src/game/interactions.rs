@@ -5,11 +5,18 @@ use crate::physics::{
 };
 use crate::progress::PlayerProgress;
 use chariot_core::{
-    player::{choices::Stat, lap_info::LapInformation},
-    questions::AudienceAction,
+    entity_location::EntityLocation,
+    player::{choices::Stat, lap_info::LapInformation, PlayerID},
+    questions::{AudienceAction, AudienceTarget, QuestionData},
+    world_entity::WorldEntityKind,
 };
 use glam::DVec3;
 
+use super::world_entities::WorldEntities;
+
+// how far ahead of a racer an audience-placed hazard shows up
+const HAZARD_PLACEMENT_DISTANCE: f64 = 6.0;
+
 pub fn get_physics_change_from_audience_action(
     audience_action: &AudienceAction,
 ) -> Option<PhysicsChangeType> {
@@ -42,6 +49,8 @@ pub fn get_stats_changes_from_audience_action(
             (Stat::MaxCarSpin, 7.5),
             (Stat::RotationReductionCoefficient, 2.0),
         ],
+        AudienceAction::SlowDown => vec![(Stat::MaxCarSpeed, 0.5)],
+        AudienceAction::SpeedBoost => vec![(Stat::MaxCarSpeed, 1.5), (Stat::CarAccelerator, 2.0)],
         AudienceAction::MoonGravity => vec![(Stat::GravityCoefficient, 0.25)],
         AudienceAction::IceRink => vec![(Stat::RollingResistanceCoefficient, 0.0)],
         AudienceAction::ExplosivePlayerCollisions => vec![(Stat::PlayerBounciness, 3.0)],
@@ -60,14 +69,65 @@ pub fn get_stats_changes_from_audience_action(
         .collect()
 }
 
+// Figure out which players an audience decision should be applied to; only
+// the first `racer_count` players are actually connected, so place-based
+// targets only consider those
+pub fn get_targeted_players(
+    target: &AudienceTarget,
+    players: &[PlayerEntity; 4],
+    racer_count: usize,
+) -> Vec<PlayerID> {
+    let racers = players.iter().enumerate().take(racer_count);
+    match target {
+        AudienceTarget::Everyone => (0..racer_count.min(players.len())).collect(),
+        AudienceTarget::Leader => racers
+            .filter(|(_, player)| player.cached_place == Some(1))
+            .map(|(id, _)| id)
+            .collect(),
+        AudienceTarget::LastPlace => racers
+            .filter(|(_, player)| player.cached_place.is_some())
+            .max_by_key(|(_, player)| player.cached_place)
+            .map(|(id, _)| id)
+            .into_iter()
+            .collect(),
+        AudienceTarget::Player(id) => {
+            if *id < racer_count {
+                vec![*id]
+            } else {
+                vec![]
+            }
+        }
+    }
+}
+
+// Questions are written for a full lobby, so leave out any options aimed at
+// a player slot nobody's racing in; otherwise the audience could vote for
+// an empty slot and nothing would happen
+pub fn options_for_racers(question: &QuestionData, racer_count: usize) -> QuestionData {
+    QuestionData {
+        prompt: question.prompt.clone(),
+        options: question
+            .options
+            .iter()
+            .filter(
+                |option| !matches!(option.target, AudienceTarget::Player(id) if id >= racer_count),
+            )
+            .cloned()
+            .collect(),
+    }
+}
+
 pub fn handle_one_time_audience_action(
     audience_action: &AudienceAction,
+    targets: &[PlayerID],
     players: &mut [PlayerEntity; 4],
+    world_entities: &mut WorldEntities,
 ) {
     match audience_action {
         AudienceAction::Backwards => {
             let backwards_rotation = glam::DQuat::from_axis_angle(DVec3::Y, std::f64::consts::PI);
-            for player in players {
+            for &target in targets {
+                let player = &mut players[target];
                 player.entity_location.unit_steer_direction =
                     backwards_rotation * player.entity_location.unit_steer_direction;
                 player.velocity = backwards_rotation * player.velocity;
@@ -132,11 +192,11 @@ pub fn handle_one_time_audience_action(
         }
 
         AudienceAction::RewindLapCounter => {
-            for player in players.iter_mut() {
+            for &target in targets {
                 if let PlayerProgress::Racing {
                     lap_info: LapInformation { lap, .. },
                     ..
-                } = &mut player.placement_data
+                } = &mut players[target].placement_data
                 {
                     if *lap > 1 {
                         *lap = *lap - 1;
@@ -145,6 +205,24 @@ pub fn handle_one_time_audience_action(
             }
         }
 
+        AudienceAction::WetFloor => {
+            for &target in targets {
+                let location = players[target].entity_location;
+                world_entities.spawn(
+                    WorldEntityKind::WetFloorSign,
+                    EntityLocation {
+                        position: location.position
+                            + location.unit_steer_direction * HAZARD_PLACEMENT_DISTANCE,
+                        unit_steer_direction: -location.unit_steer_direction,
+                        unit_upward_direction: DVec3::Y,
+                    },
+                    DVec3::ZERO,
+                    None,
+                    None,
+                );
+            }
+        }
+
         _ => {}
     }
 }
@@ -1,24 +1,170 @@
 use std::collections::VecDeque;
+use std::{fmt, fs, io};
 
-use glam::DVec2;
+use glam::DVec3;
 use serde_json::Value;
 
 use crate::{
+    chairs::get_starting_position_from_player_number,
     checkpoints::*,
-    physics::{bounding_box::BoundingBox, ramp::Ramp, trigger_entity::TriggerEntity},
+    physics::{
+        bounding_box::BoundingBox,
+        ground::GroundFace,
+        spatial_grid::{pick_mut, SpatialGrid},
+        surface::{BoostPad, Surface, SurfaceZone},
+        triangle::Triangle,
+        trigger_entity::TriggerEntity,
+    },
+    progress::PlayerProgress,
+};
+use chariot_core::entity_location::EntityLocation;
+use chariot_core::networking::ws::{TrackRect, WSAudienceBoundMessage};
+use chariot_core::player::choices::TrackMetadata;
+use chariot_core::player::PlayerID;
+use chariot_core::{
+    player::lap_info::{LapNumber, ZoneID},
+    GLOBAL_CONFIG,
 };
-use chariot_core::{player::lap_info::ZoneID, GLOBAL_CONFIG};
 
+use super::centerline::Centerline;
 use super::powerup::pickups::ItemBox;
+use super::tracks::display_name_from;
+
+// Everything that can stop a track from loading; semantic problems name the
+// mesh or node they're about so track authors know where to look
+#[derive(Debug)]
+pub enum MapLoadError {
+    Io(io::Error),
+    Gltf(gltf::Error),
+    SceneCount(usize),
+    InvalidExtras {
+        name: String,
+        error: serde_json::Error,
+    },
+    MissingPositions {
+        mesh: String,
+    },
+    VertexIndexOutOfRange {
+        mesh: String,
+        index: usize,
+        vertex_count: usize,
+    },
+    MissingTriggerType {
+        mesh: String,
+    },
+    UnknownTriggerType {
+        mesh: String,
+        trigger: String,
+    },
+    MissingCheckpointId {
+        mesh: String,
+    },
+    MissingZoneId {
+        mesh: String,
+    },
+    MissingGridSlot {
+        node: String,
+    },
+    MissingSurfaceType {
+        mesh: String,
+    },
+    UnknownSurfaceType {
+        mesh: String,
+        surface: String,
+    },
+    InvalidMinimapBounds,
+    InvalidLaps,
+    NoFinishLine,
+}
+
+impl fmt::Display for MapLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapLoadError::Io(error) => write!(f, "couldn't read the track file: {}", error),
+            MapLoadError::Gltf(error) => write!(f, "couldn't parse the track as glTF: {}", error),
+            MapLoadError::SceneCount(count) => {
+                write!(f, "track has {} scenes, but should have exactly 1", count)
+            }
+            MapLoadError::InvalidExtras { name, error } => {
+                write!(f, "'{}' has extras that aren't valid JSON: {}", name, error)
+            }
+            MapLoadError::MissingPositions { mesh } => {
+                write!(f, "mesh '{}' has a primitive without positions", mesh)
+            }
+            MapLoadError::VertexIndexOutOfRange {
+                mesh,
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "mesh '{}' uses vertex {}, but only has {} vertices",
+                mesh, index, vertex_count
+            ),
+            MapLoadError::MissingTriggerType { mesh } => {
+                write!(
+                    f,
+                    "trigger mesh '{}' doesn't say what kind of trigger it is",
+                    mesh
+                )
+            }
+            MapLoadError::UnknownTriggerType { mesh, trigger } => {
+                write!(f, "mesh '{}' has unknown trigger type '{}'", mesh, trigger)
+            }
+            MapLoadError::MissingCheckpointId { mesh } => {
+                write!(f, "checkpoint '{}' has no checkpoint_id", mesh)
+            }
+            MapLoadError::MissingZoneId { mesh } => write!(f, "zone '{}' has no zone_id", mesh),
+            MapLoadError::MissingGridSlot { node } => {
+                write!(f, "start grid node '{}' has no grid_slot", node)
+            }
+            MapLoadError::MissingSurfaceType { mesh } => {
+                write!(f, "surface mesh '{}' doesn't say what it's made of", mesh)
+            }
+            MapLoadError::UnknownSurfaceType { mesh, surface } => {
+                write!(f, "mesh '{}' has unknown surface type '{}'", mesh, surface)
+            }
+            MapLoadError::InvalidMinimapBounds => write!(
+                f,
+                "minimap_bounds should be four numbers: [min_x, max_x, min_z, max_z]"
+            ),
+            MapLoadError::InvalidLaps => write!(f, "laps should be a whole number from 1 to 255"),
+            MapLoadError::NoFinishLine => write!(f, "track has no finish line"),
+        }
+    }
+}
+
+impl std::error::Error for MapLoadError {}
+
+impl From<io::Error> for MapLoadError {
+    fn from(error: io::Error) -> Self {
+        MapLoadError::Io(error)
+    }
+}
+
+impl From<gltf::Error> for MapLoadError {
+    fn from(error: gltf::Error) -> Self {
+        MapLoadError::Gltf(error)
+    }
+}
 
 pub struct Map {
-    // Something you cannot pass through/has collision
+    // Something you cannot pass through/has collision; the boxes are just
+    // for drawing the minimap, the triangles are what players bump into
     pub colliders: Vec<BoundingBox>,
-    pub ramps: Vec<Ramp>,
+    pub wall_triangles: Vec<Triangle>,
+    // What you drive on, ramps and hills and all; anywhere not covered by
+    // this is flat floor at y = 0
+    pub ground: Vec<GroundFace>,
 
     // basically: while you're on the track, you should get a speedup (vroom vroom zoom zoom)
     pub speedup_zones: Vec<BoundingBox>,
 
+    // patches of ice, mud and so on that change how you handle on them
+    pub surface_zones: Vec<SurfaceZone>,
+
+    // pads that shove you along them
+    pub boost_pads: Vec<BoostPad>,
+
     // Map's checkpoints, which track progress through the track
     pub checkpoints: Vec<Checkpoint>,
 
@@ -28,20 +174,95 @@ pub struct Map {
     // Map's finish line, which... is the finish line
     pub finish_line: FinishLine,
 
+    // a rough line around the middle of the track, for telling how far
+    // around it everyone is
+    pub centerline: Centerline,
+
     pub powerups: Vec<ItemBox>,
+
+    // where each player starts the race, by grid slot
+    pub start_grid: Vec<EntityLocation>,
+
+    pub metadata: TrackMetadata,
+
+    // everything in the track; get far enough outside this and you've fallen off
+    pub world_bounds: BoundingBox,
+
+    // broad-phase lookups so each player only checks what's actually near
+    // them; triggers are indexed in checkpoint, zone, finish line, item box order
+    wall_grid: SpatialGrid,
+    ground_grid: SpatialGrid,
+    speedup_grid: SpatialGrid,
+    surface_grid: SpatialGrid,
+    boost_pad_grid: SpatialGrid,
+    trigger_grid: SpatialGrid,
+}
+
+// how far above the bottom of a checkpoint respawned players appear, so they
+// drop onto the track rather than into it
+const RESPAWN_DROP_HEIGHT: f64 = 1.0;
+
+// roughly a few chairs wide; small enough that a player only ever touches a
+// handful of cells
+const GRID_CELL_SIZE: f64 = 8.0;
+
+// which way a node's local z axis points, flattened onto the ground
+fn flat_facing(transform: glam::Mat4) -> DVec3 {
+    let facing = transform.transform_vector3(glam::Vec3::Z).as_dvec3();
+    DVec3::new(facing.x, 0.0, facing.z)
+        .try_normalize()
+        .unwrap_or(DVec3::Z)
 }
 
-fn import_mesh(
+// Every node in the scene along with its world transform, parents before
+// their children
+pub(super) fn scene_nodes<'a>(scene: &gltf::Scene<'a>) -> Vec<(gltf::Node<'a>, glam::Mat4)> {
+    let mut nodes = Vec::new();
+
+    // Queue of (Node, Transformation) tuples
+    let mut queue: VecDeque<(gltf::Node, glam::Mat4)> = scene
+        .nodes()
+        .map(|n| (n, glam::Mat4::IDENTITY))
+        .collect::<VecDeque<(gltf::Node, glam::Mat4)>>();
+
+    // Probably better to do this recursively but i didn't wanna change stuff like crazy, not that it really matters since this is just loading anyways
+    while let Some((node, parent_transform)) = queue.pop_front() {
+        let transform = parent_transform
+            * (match node.transform() {
+                gltf::scene::Transform::Matrix { matrix } => {
+                    glam::Mat4::from_cols_array_2d(&matrix)
+                }
+                gltf::scene::Transform::Decomposed {
+                    translation,
+                    rotation,
+                    scale,
+                } => glam::Mat4::from_scale_rotation_translation(
+                    glam::Vec3::from(scale),
+                    glam::Quat::from_array(rotation),
+                    glam::Vec3::from(translation),
+                ),
+            });
+
+        for child in node.children() {
+            queue.push_back((child, transform));
+        }
+        nodes.push((node, transform));
+    }
+
+    nodes
+}
+
+// The box around a primitive in world space, or None if it doesn't have any
+// positions to go on
+pub(super) fn import_mesh(
     buffers: &[gltf::buffer::Data],
     primitive: &gltf::Primitive,
     transform: glam::Mat4,
-) -> BoundingBox {
+) -> Option<BoundingBox> {
     let mut bounds = BoundingBox::extremes();
 
     let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
-    let vert_iter = reader
-        .read_positions()
-        .expect("Couldn't read primitive's positions!");
+    let vert_iter = reader.read_positions()?;
     let mut vert_buf = vert_iter.collect::<Vec<[f32; 3]>>();
 
     for vertex in vert_buf.iter_mut() {
@@ -53,42 +274,173 @@ fn import_mesh(
     let glam_verts = vert_buf.iter().map(|e| glam::Vec3::from_slice(e));
 
     bounds = bounds.accum(BoundingBox::from_vecs(
-        glam_verts
-            .clone()
-            .reduce(|a, e| a.min(e))
-            .unwrap()
-            .as_dvec3(),
-        glam_verts
-            .clone()
-            .reduce(|a, e| a.max(e))
-            .unwrap()
-            .as_dvec3(),
+        glam_verts.clone().reduce(|a, e| a.min(e))?.as_dvec3(),
+        glam_verts.clone().reduce(|a, e| a.max(e))?.as_dvec3(),
     ));
 
-    bounds
+    Some(bounds)
+}
+
+// The faces of a collision mesh in world space. Anything that isn't a plain
+// triangle list just becomes the box around it
+pub(super) fn import_triangles(
+    mesh_name: &str,
+    buffers: &[gltf::buffer::Data],
+    primitive: &gltf::Primitive,
+    transform: glam::Mat4,
+    mesh_bounds: &BoundingBox,
+) -> Result<Vec<Triangle>, MapLoadError> {
+    if primitive.mode() != gltf::mesh::Mode::Triangles {
+        return Ok(Triangle::from_box(mesh_bounds));
+    }
+
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+    let vertices: Vec<DVec3> = reader
+        .read_positions()
+        .ok_or_else(|| MapLoadError::MissingPositions {
+            mesh: mesh_name.to_string(),
+        })?
+        .map(|vertex| {
+            transform
+                .transform_point3(glam::Vec3::from(vertex))
+                .as_dvec3()
+        })
+        .collect();
+    let indices: Vec<usize> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().map(|index| index as usize).collect(),
+        None => (0..vertices.len()).collect(),
+    };
+
+    // indices come straight from the file, so don't trust them
+    let vertex = |index: usize| {
+        vertices
+            .get(index)
+            .copied()
+            .ok_or_else(|| MapLoadError::VertexIndexOutOfRange {
+                mesh: mesh_name.to_string(),
+                index,
+                vertex_count: vertices.len(),
+            })
+    };
+
+    let mut triangles = vec![];
+    for corners in indices.chunks_exact(3) {
+        if let Some(triangle) = Triangle::new(
+            vertex(corners[0])?,
+            vertex(corners[1])?,
+            vertex(corners[2])?,
+        ) {
+            triangles.push(triangle);
+        }
+    }
+    Ok(triangles)
+}
+
+// a node, mesh or scene's extras as JSON; things without any are just null
+fn parse_extras(name: &str, extras: &gltf::json::Extras) -> Result<Value, MapLoadError> {
+    match extras {
+        Some(extras) => {
+            serde_json::from_str(extras.get()).map_err(|error| MapLoadError::InvalidExtras {
+                name: name.to_string(),
+                error,
+            })
+        }
+        None => Ok(Value::Null),
+    }
+}
+
+// everything that makes up the shape of the track, for when the track doesn't
+// tell us how much of the world the minimap should cover
+fn outline_bounds(
+    zones: &[Zone],
+    colliders: &[BoundingBox],
+    finish_line: &FinishLine,
+) -> BoundingBox {
+    zones
+        .iter()
+        .map(|zone| zone.bounds)
+        .chain(colliders.iter().copied())
+        .fold(finish_line.bounds, |acc, bounds| acc.accum(bounds))
+}
+
+// Reads the track-wide settings out of the scene's extras, e.g.
+// { "display_name": "Office", "laps": 3, "minimap_bounds": [min_x, max_x, min_z, max_z] }
+pub(super) fn read_track_metadata(
+    scene: &gltf::Scene,
+    filename: &str,
+    fallback_minimap_bounds: TrackRect,
+) -> Result<TrackMetadata, MapLoadError> {
+    let track_data = parse_extras(scene.name().unwrap_or("<scene>"), scene.extras())?;
+
+    let display_name = match track_data.get("display_name") {
+        Some(Value::String(name)) => name.clone(),
+        _ => display_name_from(filename),
+    };
+
+    // a race with no laps would never end
+    let laps = match track_data.get("laps") {
+        Some(laps) => laps
+            .as_u64()
+            .and_then(|laps| LapNumber::try_from(laps).ok())
+            .filter(|&laps| laps > 0)
+            .ok_or(MapLoadError::InvalidLaps)?,
+        None => GLOBAL_CONFIG.number_laps,
+    };
+
+    let minimap_bounds = match track_data.get("minimap_bounds") {
+        Some(Value::Array(extents)) if extents.len() == 4 => {
+            let extents = extents
+                .iter()
+                .map(|extent| extent.as_f64().map(|extent| extent as f32))
+                .collect::<Option<Vec<f32>>>()
+                .ok_or(MapLoadError::InvalidMinimapBounds)?;
+            TrackRect {
+                min_x: extents[0],
+                max_x: extents[1],
+                min_z: extents[2],
+                max_z: extents[3],
+            }
+        }
+        Some(_) => return Err(MapLoadError::InvalidMinimapBounds),
+        None => fallback_minimap_bounds,
+    };
+
+    Ok(TrackMetadata {
+        display_name,
+        laps,
+        minimap_bounds,
+    })
+}
+
+// drops the y axis of a bounding box so the audience can draw it top-down
+pub(super) fn flatten(bounds: &BoundingBox) -> TrackRect {
+    TrackRect {
+        min_x: bounds.min_x as f32,
+        max_x: bounds.max_x as f32,
+        min_z: bounds.min_z as f32,
+        max_z: bounds.max_z as f32,
+    }
 }
 
 impl Map {
-    pub fn load(filename: String) -> core::result::Result<Map, gltf::Error> {
+    pub fn load(filename: String) -> Result<Map, MapLoadError> {
         println!(
             "loading {}, please give a sec I swear it's not lagging",
             filename
         );
-        filename.split(".").next().expect("invalid filename format");
         let map_path = format!("{}/{}.glb", GLOBAL_CONFIG.tracks_folder, filename);
-        let (document, buffers, _) = gltf::import(map_path)?;
+        let (document, buffers, _) = gltf::import_slice(fs::read(map_path)?)?;
         if document.scenes().count() != 1 {
-            panic!(
-                "Document {} has {} scenes!",
-                filename,
-                document.scenes().count()
-            );
+            return Err(MapLoadError::SceneCount(document.scenes().count()));
         }
 
         let mut colliders: Vec<BoundingBox> = Vec::new();
-        let mut ramps: Vec<Ramp> = Vec::new();
+        let mut wall_triangles: Vec<Triangle> = Vec::new();
+        let mut ground: Vec<GroundFace> = Vec::new();
 
         let mut speedup_zones: Vec<BoundingBox> = Vec::new();
+        let mut surface_zones: Vec<SurfaceZone> = Vec::new();
+        let mut boost_pads: Vec<BoostPad> = Vec::new();
 
         let mut checkpoints: Vec<Checkpoint> = Vec::new();
         let mut major_zones: Vec<Zone> = Vec::new();
@@ -99,162 +451,438 @@ impl Map {
 
         let mut powerups = Vec::new();
 
-        // Queue of (Node, Transformation) tuples
-        let mut queue: VecDeque<(gltf::Node, glam::Mat4)> = document
-            .scenes()
-            .next()
-            .expect("No root node in scene")
-            .nodes()
-            .map(|n| (n, glam::Mat4::IDENTITY))
-            .collect::<VecDeque<(gltf::Node, glam::Mat4)>>();
-
-        // Probably better to do this recursively but i didn't wanna change stuff like crazy, not that it really matters since this is just loading anyways
-        while let Some((node, parent_transform)) = queue.pop_front() {
-            let transform = parent_transform
-                * (match node.transform() {
-                    gltf::scene::Transform::Matrix { matrix } => {
-                        glam::Mat4::from_cols_array_2d(&matrix)
-                    }
-                    gltf::scene::Transform::Decomposed {
-                        translation,
-                        rotation,
-                        scale,
-                    } => glam::Mat4::from_scale_rotation_translation(
-                        glam::Vec3::from(scale),
-                        glam::Quat::from_array(rotation),
-                        glam::Vec3::from(translation),
-                    ),
-                });
+        let mut start_grid: Vec<(u64, EntityLocation)> = Vec::new();
+
+        let scene = document.scenes().next().unwrap();
+        for (node, transform) in scene_nodes(&scene) {
+            let node_name = node.name().unwrap_or("<unnamed>");
+
+            // start grid slots are empties rather than meshes; players start
+            // at the node, facing along its local z axis
+            let node_data = parse_extras(node_name, node.extras())?;
+            if let Some(Value::String(purpose)) = node_data.get("purpose") {
+                if purpose == "start_grid" {
+                    let slot = node_data
+                        .get("grid_slot")
+                        .and_then(Value::as_u64)
+                        .ok_or_else(|| MapLoadError::MissingGridSlot {
+                            node: node_name.to_string(),
+                        })?;
+                    println!("Loading node '{}' as start grid slot {}", node_name, slot);
+
+                    let position = transform.transform_point3(glam::Vec3::ZERO).as_dvec3();
+                    start_grid.push((
+                        slot,
+                        EntityLocation {
+                            position,
+                            unit_steer_direction: flat_facing(transform),
+                            unit_upward_direction: DVec3::Y,
+                        },
+                    ));
+                }
+            }
 
             if let Some(mesh) = node.mesh() {
-                if let Some(extras) = mesh.extras().as_ref() {
-                    let mesh_data: Value = serde_json::from_str(extras.as_ref().get()).unwrap();
-                    if let Some(Value::String(purpose)) = mesh_data.get("purpose") {
-                        for (_, primitive) in mesh.primitives().enumerate() {
-                            let mesh_bounds = import_mesh(&buffers, &primitive, transform);
-
-                            if purpose == "trigger" {
-                                if let Some(Value::String(trigger_type)) = mesh_data.get("trigger")
-                                {
-                                    if trigger_type == "checkpoint" {
-                                        let idx = mesh_data
-                                            .get("checkpoint_id")
-                                            .unwrap()
-                                            .as_u64()
-                                            .unwrap();
-                                        println!(
-                                            "Loading mesh '{}' as a trigger_checkpoint_{}",
-                                            mesh.name().unwrap_or("<unnamed>"),
-                                            idx
-                                        );
-                                        checkpoints.push(Checkpoint::new(idx, mesh_bounds));
-                                    } else if trigger_type == "zone" {
-                                        let idx =
-                                            mesh_data.get("zone_id").unwrap().as_u64().unwrap();
-                                        println!(
-                                            "Loading mesh '{}' as a trigger_zone_{}",
-                                            mesh.name().unwrap_or("<unnamed>"),
-                                            idx
-                                        );
-                                        last_zone = idx.max(last_zone);
-                                        major_zones.push(Zone::new(idx, mesh_bounds));
-                                    } else if trigger_type == "finish_line" {
-                                        println!(
-                                            "Loading mesh '{}' as a trigger_finish_line",
-                                            mesh.name().unwrap_or("<unnamed>")
-                                        );
-                                        finish_line = Some(FinishLine::new(mesh_bounds, 1));
-                                        // } else if trigger_type == "powerup" {
-                                    } else if trigger_type == "powerup" {
-                                        println!(
-                                            "Loading mesh '{}' as a trigger_powerup",
-                                            mesh.name().unwrap_or("<unnamed>")
-                                        );
-                                        powerups.push(ItemBox::new(mesh_bounds));
-                                    } else {
-                                        panic!("Unknown trigger type '{}'!", trigger_type);
-                                    }
-                                }
-                            } else if purpose == "collision" {
-                                println!(
-                                    "Loading mesh '{}' as a collider",
-                                    mesh.name().unwrap_or("<unnamed>")
-                                );
-                                colliders.push(mesh_bounds);
-                            } else if purpose == "ramp" {
-                                let incline_direction: String = mesh_data
-                                    .get("incline_direction")
-                                    .expect("Ramps should have an incline direction!")
-                                    .to_string();
-
-                                let incline_direction_vec = if incline_direction == "pos_x" {
-                                    -1.0 * DVec2::X
-                                } else if incline_direction == "neg_x" {
-                                    DVec2::X
-                                } else if incline_direction == "pos_z" {
-                                    -1.0 * DVec2::Y
+                let mesh_name = mesh.name().unwrap_or("<unnamed>");
+                let missing_positions = || MapLoadError::MissingPositions {
+                    mesh: mesh_name.to_string(),
+                };
+
+                let mesh_data = parse_extras(mesh_name, mesh.extras())?;
+                if let Some(Value::String(purpose)) = mesh_data.get("purpose") {
+                    for (_, primitive) in mesh.primitives().enumerate() {
+                        let mesh_bounds = import_mesh(&buffers, &primitive, transform)
+                            .ok_or_else(missing_positions)?;
+
+                        if purpose == "trigger" {
+                            if let Some(Value::String(trigger_type)) = mesh_data.get("trigger") {
+                                if trigger_type == "checkpoint" {
+                                    let idx = mesh_data
+                                        .get("checkpoint_id")
+                                        .and_then(Value::as_u64)
+                                        .ok_or_else(|| MapLoadError::MissingCheckpointId {
+                                            mesh: mesh_name.to_string(),
+                                        })?;
+                                    println!(
+                                        "Loading mesh '{}' as a trigger_checkpoint_{}",
+                                        mesh_name, idx
+                                    );
+                                    // players put back here get dropped in at
+                                    // the bottom middle of the checkpoint,
+                                    // facing along its local z axis
+                                    let respawn = EntityLocation {
+                                        position: DVec3::new(
+                                            mesh_bounds.pos().x,
+                                            mesh_bounds.min_y + RESPAWN_DROP_HEIGHT,
+                                            mesh_bounds.pos().z,
+                                        ),
+                                        unit_steer_direction: flat_facing(transform),
+                                        unit_upward_direction: DVec3::Y,
+                                    };
+                                    checkpoints.push(Checkpoint::new(idx, mesh_bounds, respawn));
+                                } else if trigger_type == "zone" {
+                                    let idx = mesh_data
+                                        .get("zone_id")
+                                        .and_then(Value::as_u64)
+                                        .ok_or_else(|| MapLoadError::MissingZoneId {
+                                            mesh: mesh_name.to_string(),
+                                        })?;
+                                    println!(
+                                        "Loading mesh '{}' as a trigger_zone_{}",
+                                        mesh_name, idx
+                                    );
+                                    last_zone = idx.max(last_zone);
+                                    major_zones.push(Zone::new(idx, mesh_bounds));
+                                } else if trigger_type == "finish_line" {
+                                    println!(
+                                        "Loading mesh '{}' as a trigger_finish_line",
+                                        mesh_name
+                                    );
+                                    finish_line = Some(FinishLine::new(mesh_bounds, 1));
+                                    // } else if trigger_type == "powerup" {
+                                } else if trigger_type == "powerup" {
+                                    println!("Loading mesh '{}' as a trigger_powerup", mesh_name);
+                                    powerups.push(ItemBox::new(mesh_bounds));
                                 } else {
-                                    DVec2::Y
-                                };
-
-                                let ramp = Ramp {
-                                    footprint: [
-                                        [mesh_bounds.min_x, mesh_bounds.max_x],
-                                        [mesh_bounds.min_z, mesh_bounds.max_z],
-                                    ],
-                                    min_height: mesh_bounds.min_y,
-                                    max_height: mesh_bounds.max_y,
-                                    incline_direction: incline_direction_vec,
-                                };
-                                ramps.push(ramp);
-                            } else if purpose == "speedup" {
-                                speedup_zones.push(mesh_bounds);
+                                    return Err(MapLoadError::UnknownTriggerType {
+                                        mesh: mesh_name.to_string(),
+                                        trigger: trigger_type.clone(),
+                                    });
+                                }
                             } else {
-                                // panic!(
-                                //     "Mesh '{}' has unknown purpose '{}'!",
-                                //     mesh.name().unwrap_or("<unnamed>"),
-                                //     purpose
-                                // );
+                                return Err(MapLoadError::MissingTriggerType {
+                                    mesh: mesh_name.to_string(),
+                                });
                             }
-
-                            world_bounds = world_bounds.accum(mesh_bounds);
+                        } else if purpose == "collision" {
+                            println!("Loading mesh '{}' as a collider", mesh_name);
+                            colliders.push(mesh_bounds);
+                            wall_triangles.extend(import_triangles(
+                                mesh_name,
+                                &buffers,
+                                &primitive,
+                                transform,
+                                &mesh_bounds,
+                            )?);
+                        } else if purpose == "ground" || purpose == "ramp" {
+                            // anything too steep to drive up is a wall instead
+                            println!("Loading mesh '{}' as {}", mesh_name, purpose);
+                            for triangle in import_triangles(
+                                mesh_name,
+                                &buffers,
+                                &primitive,
+                                transform,
+                                &mesh_bounds,
+                            )? {
+                                if GroundFace::is_driveable(&triangle) {
+                                    ground.push(GroundFace {
+                                        triangle,
+                                        boosts_uphill: purpose == "ramp",
+                                    });
+                                } else {
+                                    wall_triangles.push(triangle);
+                                }
+                            }
+                        } else if purpose == "speedup" {
+                            speedup_zones.push(mesh_bounds);
+                        } else if purpose == "surface" {
+                            let surface_type = mesh_data
+                                .get("surface")
+                                .and_then(Value::as_str)
+                                .ok_or_else(|| MapLoadError::MissingSurfaceType {
+                                    mesh: mesh_name.to_string(),
+                                })?;
+                            let surface = Surface::from_name(surface_type).ok_or_else(|| {
+                                MapLoadError::UnknownSurfaceType {
+                                    mesh: mesh_name.to_string(),
+                                    surface: surface_type.to_string(),
+                                }
+                            })?;
+                            println!("Loading mesh '{}' as {:?}", mesh_name, surface);
+                            surface_zones.push(SurfaceZone {
+                                bounds: mesh_bounds,
+                                surface,
+                            });
+                        } else if purpose == "boost_pad" {
+                            // pads boost you along their local z axis, same as
+                            // which way the start grid faces
+                            println!("Loading mesh '{}' as a boost pad", mesh_name);
+                            let forward = transform.transform_vector3(glam::Vec3::Z).as_dvec3();
+                            boost_pads.push(BoostPad {
+                                bounds: mesh_bounds,
+                                forward: forward.try_normalize().unwrap_or(DVec3::Z),
+                            });
+                        } else {
+                            // panic!(
+                            //     "Mesh '{}' has unknown purpose '{}'!",
+                            //     mesh_name,
+                            //     purpose
+                            // );
                         }
+
+                        world_bounds = world_bounds.accum(mesh_bounds);
                     }
                 }
             }
-
-            for child in node.children() {
-                queue.push_back((child, transform));
-            }
         }
 
+        let mut finish_line = finish_line
+            .ok_or(MapLoadError::NoFinishLine)?
+            .set_last_zone(last_zone);
+
+        let metadata = read_track_metadata(
+            &scene,
+            &filename,
+            flatten(&outline_bounds(&major_zones, &colliders, &finish_line)),
+        )?;
+        finish_line.set_laps(metadata.laps);
+        let centerline = Centerline::new(&finish_line, &major_zones);
+
+        start_grid.sort_by_key(|(slot, _)| *slot);
+        let start_grid = start_grid
+            .into_iter()
+            .map(|(_, location)| location)
+            .collect();
+
+        let wall_grid = SpatialGrid::new(
+            &wall_triangles
+                .iter()
+                .map(Triangle::bounding_box)
+                .collect::<Vec<_>>(),
+            GRID_CELL_SIZE,
+        );
+        let ground_grid = SpatialGrid::new(
+            &ground
+                .iter()
+                .map(|face| face.triangle.bounding_box())
+                .collect::<Vec<_>>(),
+            GRID_CELL_SIZE,
+        );
+        let speedup_grid = SpatialGrid::new(&speedup_zones, GRID_CELL_SIZE);
+        let surface_grid = SpatialGrid::new(
+            &surface_zones
+                .iter()
+                .map(|zone| zone.bounds)
+                .collect::<Vec<_>>(),
+            GRID_CELL_SIZE,
+        );
+        let boost_pad_grid = SpatialGrid::new(
+            &boost_pads.iter().map(|pad| pad.bounds).collect::<Vec<_>>(),
+            GRID_CELL_SIZE,
+        );
+        let trigger_bounds: Vec<BoundingBox> = checkpoints
+            .iter()
+            .map(|checkpoint| checkpoint.bounds)
+            .chain(major_zones.iter().map(|zone| zone.bounds))
+            .chain(std::iter::once(finish_line.bounds))
+            .chain(powerups.iter().map(|item_box| item_box.bounds))
+            .collect();
+        let trigger_grid = SpatialGrid::new(&trigger_bounds, GRID_CELL_SIZE);
+
         println!("done!");
 
-        core::result::Result::Ok(Self {
+        Ok(Self {
             colliders,
-            ramps,
+            wall_triangles,
+            ground,
             speedup_zones,
+            surface_zones,
+            boost_pads,
             checkpoints,
             major_zones,
-            finish_line: finish_line
-                .expect(format!("Map {} has no finish line!", filename).as_str())
-                .set_last_zone(last_zone),
+            finish_line,
+            centerline,
             powerups,
+            start_grid,
+            metadata,
+            world_bounds,
+            wall_grid,
+            ground_grid,
+            speedup_grid,
+            surface_grid,
+            boost_pad_grid,
+            trigger_grid,
         })
     }
 
+    // the top-down shape of the track, for the audience's minimap
+    pub fn get_track_outline(&self) -> WSAudienceBoundMessage {
+        let mut zones = self.major_zones.clone();
+        zones.sort_by_key(|zone| zone.id);
+
+        WSAudienceBoundMessage::TrackOutline {
+            bounds: self.metadata.minimap_bounds,
+            zones: zones.iter().map(|zone| flatten(&zone.bounds)).collect(),
+            walls: self.colliders.iter().map(flatten).collect(),
+            finish_line: flatten(&self.finish_line.bounds),
+        }
+    }
+
+    // tracks without enough grid slots fall back to lining players up where
+    // our original track's start line is
+    pub fn get_start_location(&self, player_num: PlayerID) -> EntityLocation {
+        self.start_grid
+            .get(player_num)
+            .copied()
+            .unwrap_or(EntityLocation {
+                position: get_starting_position_from_player_number(player_num),
+                unit_steer_direction: DVec3::Z,
+                unit_upward_direction: DVec3::Y,
+            })
+    }
+
+    // whether someone at `position` has fallen off (or been flung out of)
+    // the track
+    pub fn is_out_of_bounds(&self, position: DVec3) -> bool {
+        let margin = GLOBAL_CONFIG.respawn_out_of_bounds_distance;
+        let bounds = &self.world_bounds;
+        position.x < bounds.min_x - margin
+            || position.x > bounds.max_x + margin
+            || position.z < bounds.min_z - margin
+            || position.z > bounds.max_z + margin
+            || position.y < bounds.min_y - margin
+    }
+
+    // where to put a player back on the track: the last checkpoint they went
+    // through, or where they started if they haven't been through any yet
+    pub fn get_respawn_location(
+        &self,
+        player_num: PlayerID,
+        progress: &PlayerProgress,
+    ) -> EntityLocation {
+        let last_checkpoint = match progress {
+            PlayerProgress::Racing { lap_info } => lap_info.last_checkpoint,
+            _ => None,
+        };
+
+        last_checkpoint
+            .and_then(|id| {
+                self.checkpoints
+                    .iter()
+                    .find(|checkpoint| checkpoint.id == id)
+            })
+            .map(|checkpoint| checkpoint.respawn)
+            .unwrap_or_else(|| self.get_start_location(player_num))
+    }
+
+    fn zone_bounds(&self, id: ZoneID) -> Option<BoundingBox> {
+        self.major_zones
+            .iter()
+            .find(|zone| zone.id == id)
+            .map(|zone| zone.bounds)
+    }
+
+    // where players in `zone` have to get to next: the next zone, or the
+    // finish line once they're through the last one
+    pub fn next_zone_bounds(&self, zone: ZoneID) -> Option<BoundingBox> {
+        if zone == self.finish_line.last_zone() {
+            Some(self.finish_line.bounds)
+        } else {
+            self.zone_bounds(zone + 1)
+        }
+    }
+
+    // the zone players came out of to get into `zone`; nothing before the
+    // first one, since that's right by the finish line
+    pub fn previous_zone_bounds(&self, zone: ZoneID) -> Option<BoundingBox> {
+        if zone == 0 {
+            None
+        } else {
+            self.zone_bounds(zone - 1)
+        }
+    }
+
+    pub fn get_item_box_positions(&self) -> Vec<DVec3> {
+        self.powerups
+            .iter()
+            .map(|item_box| item_box.bounds.pos())
+            .collect()
+    }
+
+    // wall faces anywhere near the given bounds, in the same order as
+    // `wall_triangles`
+    pub fn walls_near(&self, bounds: &BoundingBox) -> Vec<Triangle> {
+        self.wall_grid
+            .query(bounds)
+            .into_iter()
+            .map(|index| self.wall_triangles[index])
+            .collect()
+    }
+
+    pub fn ground_near(&self, bounds: &BoundingBox) -> Vec<GroundFace> {
+        self.ground_grid
+            .query(bounds)
+            .into_iter()
+            .map(|index| self.ground[index])
+            .collect()
+    }
+
+    pub fn speedup_zones_near(&self, bounds: &BoundingBox) -> Vec<BoundingBox> {
+        self.speedup_grid
+            .query(bounds)
+            .into_iter()
+            .map(|index| self.speedup_zones[index])
+            .collect()
+    }
+
+    // what the track's made of under the given bounds, if it's anything
+    // special; the first one found wins where they overlap
+    pub fn surface_under(&self, bounds: &BoundingBox) -> Option<Surface> {
+        self.surface_grid
+            .query(bounds)
+            .into_iter()
+            .map(|index| self.surface_zones[index])
+            .find(|zone| zone.bounds.is_colliding(bounds))
+            .map(|zone| zone.surface)
+    }
+
+    // which way the boost pad under the given bounds points, if there is one
+    pub fn boost_pad_under(&self, bounds: &BoundingBox) -> Option<DVec3> {
+        self.boost_pad_grid
+            .query(bounds)
+            .into_iter()
+            .map(|index| self.boost_pads[index])
+            .find(|pad| pad.bounds.is_colliding(bounds))
+            .map(|pad| pad.forward)
+    }
+
     // good god figuring out type stuff here made me want to pivot to javascript permanently
-    pub fn trigger_iter(&mut self) -> impl Iterator<Item = &mut dyn TriggerEntity> {
-        self.checkpoints
-            .iter_mut()
-            .map(|c| c as &mut dyn TriggerEntity)
-            .chain(
-                self.major_zones
-                    .iter_mut()
-                    .map(|z| z as &mut dyn TriggerEntity),
-            )
-            .chain(std::iter::once(
-                &mut self.finish_line as &mut dyn TriggerEntity,
-            ))
+    pub fn triggers_near(&mut self, bounds: &BoundingBox) -> Vec<&mut dyn TriggerEntity> {
+        let nearby = self.trigger_grid.query(bounds);
+
+        let first_zone = self.checkpoints.len();
+        let finish_line = first_zone + self.major_zones.len();
+        let first_item_box = finish_line + 1;
+        let indices_between = |start: usize, end: usize| -> Vec<usize> {
+            nearby
+                .iter()
+                .filter(|&&index| index >= start && index < end)
+                .map(|index| index - start)
+                .collect()
+        };
+        let checkpoint_indices = indices_between(0, first_zone);
+        let zone_indices = indices_between(first_zone, finish_line);
+        let item_box_indices = indices_between(first_item_box, usize::MAX);
+
+        let mut triggers: Vec<&mut dyn TriggerEntity> = vec![];
+        triggers.extend(
+            pick_mut(&mut self.checkpoints, &checkpoint_indices)
+                .into_iter()
+                .map(|c| c as &mut dyn TriggerEntity),
+        );
+        triggers.extend(
+            pick_mut(&mut self.major_zones, &zone_indices)
+                .into_iter()
+                .map(|z| z as &mut dyn TriggerEntity),
+        );
+        if nearby.contains(&finish_line) {
+            triggers.push(&mut self.finish_line as &mut dyn TriggerEntity);
+        }
+        triggers.extend(
+            pick_mut(&mut self.powerups, &item_box_indices)
+                .into_iter()
+                .map(|p| p as &mut dyn TriggerEntity),
+        );
+        triggers
     }
 }
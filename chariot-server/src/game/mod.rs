@@ -3,47 +3,80 @@ use std::net::TcpListener;
 use std::thread::{self};
 use std::time::{Duration, Instant};
 
-use chariot_core::player::choices::{Chair, PlayerChoices, Track};
+use chariot_core::player::choices::{Chair, PlayerChoices, TrackInfo};
 use chariot_core::player::player_inputs::{EngineStatus, PlayerInputs};
 use chariot_core::player::{lap_info::LapInformation, player_inputs::InputEvent, PlayerID};
 use glam::DVec3;
 
 use chariot_core::entity_location::EntityLocation;
-use chariot_core::networking::ws::{QuestionResult, Standing, WSAudienceBoundMessage};
+use chariot_core::networking::ws::{
+    QuestionResult, RacerPosition, Standing, WSAudienceBoundMessage,
+};
 use chariot_core::networking::Uuid;
 use chariot_core::networking::{
     ClientBoundPacket, ClientConnection, ServerBoundPacket, WebSocketConnection,
 };
-use chariot_core::player::lap_info::Placement;
+use chariot_core::player::lap_info::{LapNumber, Placement};
 use chariot_core::questions::{QuestionData, QUESTIONS};
 use chariot_core::GLOBAL_CONFIG;
 
 use crate::chairs::get_player_start_physics_properties;
+use crate::physics::ground::GroundSample;
 use crate::physics::physics_changes::PhysicsChange;
 use crate::physics::player_entity::PlayerEntity;
-use crate::physics::ramp::RampCollisionResult;
-use crate::progress::PlayerProgress;
+use crate::physics::timestep::FixedTimestep;
+use crate::progress::{LapTimer, PlayerProgress};
 
+use self::emotes::EmoteAggregator;
 use self::interactions::{
     get_physics_change_from_audience_action, get_stats_changes_from_audience_action,
-    handle_one_time_audience_action,
+    get_targeted_players, handle_one_time_audience_action, options_for_racers,
 };
 use self::map::Map;
 use self::phase::*;
-
+use self::powerup::action::activate_powerup;
+use self::predictions::AudienceLedger;
+use self::tracks::{discover_tracks, pick_voted_track};
+use self::world_entities::WorldEntities;
+use self::wrong_way::{looks_wrong_way, WrongWayTracker};
+
+mod centerline;
+mod emotes;
 mod interactions;
 mod map;
 mod phase;
 pub mod powerup;
+mod predictions;
+mod tracks;
+pub mod validation;
 mod voting;
+mod world_entities;
+mod wrong_way;
+
+#[cfg(test)]
+mod tests;
 
 pub struct GameServer {
     listener: TcpListener,
     ws_server: TcpListener,
     connections: Vec<ClientConnection>,
     ws_connections: HashMap<Uuid, WebSocketConnection>,
+    // persists across races so the audience can build up points over a session
+    audience_ledger: AudienceLedger,
+    audience_emotes: EmoteAggregator,
     game_state: ServerGameState,
+    // every track we found on startup, in the order clients list them
+    available_tracks: Vec<TrackInfo>,
     tick_counter: u128,
+    last_audience_position_sync: Instant,
+    // how many physics steps each tick gets to catch up on
+    physics_clock: FixedTimestep,
+    // what we last told clients about everyone's drifting
+    last_drift_sync: [Option<f32>; 4],
+    // what we last told each client about the gap to the player ahead of them
+    last_gap_sync: [Option<f32>; 4],
+    // whether each player's been told they're going the wrong way
+    wrong_way: [WrongWayTracker; 4],
 }
 
 pub struct ServerGameState {
@@ -53,6 +86,9 @@ pub struct ServerGameState {
     players: [PlayerEntity; 4],
 
     map: Option<Map>,
+
+    // wet floor signs and anything else spawned mid-race
+    world_entities: WorldEntities,
 }
 
 impl GameServer {
@@ -66,12 +102,17 @@ impl GameServer {
         println!("game server now listening on {}", ip_addr);
         let ws_server =
             TcpListener::bind(&GLOBAL_CONFIG.ws_server_port).expect("could not bind to ws server");
+        ws_server
+            .set_nonblocking(true)
+            .expect("Couldn't set the ws server to be non-blocking!");
 
         GameServer {
             listener,
             ws_server,
             connections: Vec::new(),
             ws_connections: HashMap::new(),
+            audience_ledger: AudienceLedger::default(),
+            audience_emotes: EmoteAggregator::new(),
             game_state: ServerGameState {
                 // notable: we don't allow more than 4 players
                 phase: GamePhase::ConnectingAndChoosingSettings {
@@ -81,8 +122,18 @@ impl GameServer {
                 players: [0, 1, 2, 3]
                     .map(|num| get_player_start_physics_properties(&Chair::Swivel, num)),
                 map: None,
+                world_entities: WorldEntities::default(),
             },
+            available_tracks: discover_tracks(),
             tick_counter: 0,
+            last_audience_position_sync: Instant::now(),
+            physics_clock: FixedTimestep::new(
+                Duration::from_millis(GLOBAL_CONFIG.server_tick_ms),
+                GLOBAL_CONFIG.physics_substeps,
+            ),
+            last_drift_sync: [None; 4],
+            last_gap_sync: [None; 4],
+            wrong_way: Default::default(),
         }
     }
 
@@ -116,7 +167,23 @@ impl GameServer {
                 .iter_mut()
                 .for_each(|con| con.sync_outgoing());
 
-            self.ws_connections.retain(|_, con| con.sync_outgoing());
+            let audience_count = self.ws_connections.len();
+            let audience_emotes = &mut self.audience_emotes;
+            self.ws_connections.retain(|id, con| {
+                let still_open = con.sync_outgoing();
+                if !still_open {
+                    audience_emotes.forget(id);
+                }
+                still_open
+            });
+            if self.ws_connections.len() != audience_count {
+                println!("lost an audience connection!");
+                let total_connections = self.ws_connections.len();
+                GameServer::broadcast_ws(
+                    &mut self.ws_connections,
+                    WSAudienceBoundMessage::AudienceCount(total_connections),
+                );
+            }
 
             // wait until server tick time has elapsed
             if let Some(remaining_tick_duration) =
@@ -139,6 +206,7 @@ impl GameServer {
     fn process_incoming_packets(&mut self) {
         let mut need_to_broadcast: Vec<ClientBoundPacket> = vec![];
         let mut audience_need_to_broadcast: Vec<WSAudienceBoundMessage> = vec![];
+        let racer_count = self.connections.len();
         for (player_num, connection) in self.connections.iter_mut().enumerate() {
             while let Some(packet) = connection.pop_incoming() {
                 match packet {
@@ -171,6 +239,7 @@ impl GameServer {
                                             chair: chair.to_string(),
                                             rank: (idx as u8) + 1,
                                             lap: 0,
+                                            progress: 0.0,
                                         }
                                     }),
                                 ));
@@ -180,7 +249,17 @@ impl GameServer {
                     },
                     ServerBoundPacket::MapSelect(new_map) => match &mut self.game_state.phase {
                         GamePhase::ConnectingAndChoosingSettings { player_choices, .. } => {
-                            if let Some(PlayerChoices { map, .. }) = &mut player_choices[player_num]
+                            if !self
+                                .available_tracks
+                                .iter()
+                                .any(|info| info.track == new_map)
+                            {
+                                println!(
+                                    "Player #{} voted for {}, which we don't have!",
+                                    player_num, new_map
+                                );
+                            } else if let Some(PlayerChoices { map, .. }) =
+                                &mut player_choices[player_num]
                             {
                                 println!(
                                     "Setting player #{}'s map vote to {}!",
@@ -253,7 +332,54 @@ impl GameServer {
                                 .player_inputs
                                 .rotation_status = status;
                         }
+                        InputEvent::Drift(drifting) => {
+                            let player = &mut self.game_state.players[player_num];
+                            player.player_inputs.drifting = drifting;
+                            if !drifting {
+                                player.release_drift(Instant::now());
+                            }
+                        }
                     },
+                    ServerBoundPacket::UsePowerup => {
+                        if let GamePhase::PlayingGame { .. } = self.game_state.phase {
+                            if let Some(powerup) =
+                                self.game_state.players[player_num].current_powerup.take()
+                            {
+                                println!(
+                                    "Player #{} used their {}!",
+                                    player_num,
+                                    powerup.get_name()
+                                );
+                                let targets = activate_powerup(
+                                    powerup,
+                                    player_num,
+                                    &mut self.game_state.players,
+                                    racer_count,
+                                    &mut self.game_state.world_entities,
+                                );
+                                need_to_broadcast.push(ClientBoundPacket::PowerupActivated {
+                                    user: player_num,
+                                    powerup,
+                                    targets,
+                                });
+                            }
+                        }
+                    }
+                    ServerBoundPacket::RequestRespawn => {
+                        if let (GamePhase::PlayingGame { .. }, Some(map)) =
+                            (&self.game_state.phase, &self.game_state.map)
+                        {
+                            let player = &mut self.game_state.players[player_num];
+                            if let PlayerProgress::Racing { .. } = player.placement_data {
+                                respawn_player(player, player_num, map, Instant::now());
+                                need_to_broadcast
+                                    .push(ClientBoundPacket::PlayerRespawned(player_num));
+                                // we've just put them back facing the right way
+                                self.wrong_way[player_num].reset();
+                                connection.push_outgoing(ClientBoundPacket::WrongWay(false));
+                            }
+                        }
+                    }
                     ServerBoundPacket::NextGame => {
                         if let GamePhase::AllPlayersDone(_placements) = self.game_state.phase {
                             println!("Starting next game!");
@@ -266,6 +392,8 @@ impl GameServer {
                             });
                             self.game_state.map = None;
                             need_to_broadcast.push(ClientBoundPacket::StartNextGame);
+                            audience_need_to_broadcast
+                                .push(WSAudienceBoundMessage::PredictionsOpen(true));
                         }
                     }
                 }
@@ -282,6 +410,15 @@ impl GameServer {
         }
     }
 
+    // how many laps the current race is, if there is one
+    fn lap_count(&self) -> LapNumber {
+        self.game_state
+            .map
+            .as_ref()
+            .map(|map| map.metadata.laps)
+            .unwrap_or(GLOBAL_CONFIG.number_laps)
+    }
+
     // sends a message to all connected web clients
     fn broadcast_ws(
         ws_connections: &mut HashMap<Uuid, WebSocketConnection>,
@@ -312,9 +449,14 @@ impl GameServer {
                                 idx
                             );
                             self.connections.push(ClientConnection::new(socket));
-                            self.connections.last_mut().unwrap().push_outgoing(
-                                ClientBoundPacket::PlayerNumber(idx, player_choices.clone()),
-                            );
+                            let connection = self.connections.last_mut().unwrap();
+                            connection.push_outgoing(ClientBoundPacket::AvailableTracks(
+                                self.available_tracks.clone(),
+                            ));
+                            connection.push_outgoing(ClientBoundPacket::PlayerNumber(
+                                idx,
+                                player_choices.clone(),
+                            ));
                             player_choices[idx] = Some(Default::default());
 
                             // go tell everyone else we have a new player
@@ -332,15 +474,59 @@ impl GameServer {
                             || idx >= self.connections.len()
                     }) {
                         println!("Players ready! Loading...");
-                        let track = Track::Track; // TODO figure out real voted track
+                        let track = pick_voted_track(
+                            player_choices,
+                            &self.available_tracks,
+                            self.connections.len(),
+                        );
+                        println!("Players voted for {}!", track);
+
+                        // a broken track shouldn't take the whole server down;
+                        // send everyone back to the lobby to pick something else
+                        let map = match Map::load(track.to_string()) {
+                            Ok(map) => map,
+                            Err(err) => {
+                                println!("Couldn't load {}: {}", track, err);
+                                self.available_tracks.retain(|info| info.track != track);
+
+                                let player_count = self.connections.len();
+                                for choices in player_choices.iter_mut().flatten() {
+                                    choices.ready = false;
+                                }
+                                for conn in self.connections.iter_mut() {
+                                    conn.push_outgoing(ClientBoundPacket::TrackLoadFailed {
+                                        track: track.clone(),
+                                        reason: err.to_string(),
+                                    });
+                                    conn.push_outgoing(ClientBoundPacket::AvailableTracks(
+                                        self.available_tracks.clone(),
+                                    ));
+                                    for player_num in 0..player_count {
+                                        conn.push_outgoing(ClientBoundPacket::PlayerReadyStatus(
+                                            player_num, false,
+                                        ));
+                                    }
+                                }
+                                return;
+                            }
+                        };
+                        let item_box_positions = map.get_item_box_positions();
 
                         for (player_num, conn) in self.connections.iter_mut().enumerate() {
-                            self.game_state.players[player_num] =
-                                get_player_start_physics_properties(
-                                    &player_choices[player_num].as_ref().unwrap().chair,
-                                    player_num,
-                                );
+                            let player = &mut self.game_state.players[player_num];
+                            *player = get_player_start_physics_properties(
+                                &player_choices[player_num].as_ref().unwrap().chair,
+                                player_num,
+                            );
+                            player.entity_location = map.get_start_location(player_num);
+
                             conn.push_outgoing(ClientBoundPacket::LoadGame(track.clone()));
+                            conn.push_outgoing(ClientBoundPacket::TrackLoaded(
+                                map.metadata.clone(),
+                            ));
+                            conn.push_outgoing(ClientBoundPacket::ItemBoxesPlaced(
+                                item_box_positions.clone(),
+                            ));
                         }
 
                         self.game_state.phase = GamePhase::WaitingForPlayerLoad {
@@ -352,10 +538,9 @@ impl GameServer {
                                 .unwrap(),
                         };
 
-                        self.game_state.map = Some(
-                            Map::load(track.to_string())
-                                .expect("Couldn't load the map on the server!"),
-                        );
+                        GameServer::broadcast_ws(&mut self.ws_connections, map.get_track_outline());
+                        self.game_state.map = Some(map);
+                        self.game_state.world_entities = WorldEntities::default();
                     }
                 }
             }
@@ -370,16 +555,24 @@ impl GameServer {
                     for connection in &mut self.connections {
                         connection.push_outgoing(ClientBoundPacket::GameStart(time_until_start));
                     }
+                    GameServer::broadcast_ws(
+                        &mut self.ws_connections,
+                        WSAudienceBoundMessage::PredictionsOpen(false),
+                    );
                 }
             }
 
             GamePhase::CountingDownToGameStart(countdown_end_time) => {
                 if now > *countdown_end_time {
                     println!("Go!!!");
+                    self.wrong_way = Default::default();
+                    self.last_gap_sync = [None; 4];
                     [0, 1, 2, 3].map(|i| {
-                        self.game_state.players[i].placement_data = PlayerProgress::Racing {
+                        let player = &mut self.game_state.players[i];
+                        player.placement_data = PlayerProgress::Racing {
                             lap_info: LapInformation::new(),
-                        }
+                        };
+                        player.lap_timer = LapTimer::starting_at(now - player.game_start_time);
                     });
                     // transition to playing game after countdown
                     let vote_cooldown_time = now + Duration::new(10, 0);
@@ -389,16 +582,10 @@ impl GameServer {
                         question_idx: 0,
                     };
                     // tell the audience that we have player placement now
+                    let standings = self.race_standings();
                     GameServer::broadcast_ws(
                         &mut self.ws_connections,
-                        WSAudienceBoundMessage::Standings([0, 1, 2, 3].map(|idx| -> Standing {
-                            Standing {
-                                name: idx.to_string(),
-                                chair: self.game_state.players[idx].chair.to_string(),
-                                rank: idx as u8,
-                                lap: 1,
-                            }
-                        })),
+                        WSAudienceBoundMessage::Standings(standings),
                     );
                     GameServer::broadcast_ws(
                         &mut self.ws_connections,
@@ -426,76 +613,115 @@ impl GameServer {
                     }
                 }
 
-                let ramps = &self
-                    .game_state
-                    .map
-                    .as_ref()
-                    .expect("No map loaded in game loop!")
-                    .ramps
-                    .clone();
-
-                let mut per_player_current_ramps: Vec<RampCollisionResult> = vec![];
                 let mut original_player_inputs: Vec<PlayerInputs> = vec![];
 
-                // update bounding box dimensions and temporary physics changes for each player
+                // expire temporary physics changes for each player, and let
+                // whichever are left have their way with the inputs
                 for player in &mut self.game_state.players {
-                    player.update_bounding_box();
                     player
                         .physics_changes
                         .retain(|change| change.expiration_time > now);
                     player
                         .stats_changes
                         .retain(|change| change.expiration_time > now);
+                    // these all went out last tick
+                    player.sound_effects.clear();
 
                     original_player_inputs.push(player.player_inputs);
                     player.change_inputs_per_physics_changes();
-                    let ramp_collision_result = player.update_upwards_from_ramps(ramps);
-                    per_player_current_ramps.push(ramp_collision_result);
                 }
 
-                let others = |this_index: usize| -> Vec<&PlayerEntity> {
-                    self.game_state
-                        .players
-                        .iter()
-                        .enumerate()
-                        .filter(|(other_index, _)| *other_index != this_index)
-                        .map(|(_, player_entity)| player_entity)
-                        .collect()
-                };
+                let before_tick = [0, 1, 2, 3].map(|n| {
+                    let player = &self.game_state.players[n];
+                    (
+                        player.current_powerup,
+                        player.placement_data,
+                        player.lap_timer.laps.len(),
+                    )
+                });
 
-                let colliders = &self
+                let map = self
                     .game_state
                     .map
-                    .as_ref()
-                    .expect("No map loaded in game loop!")
-                    .colliders
-                    .clone();
+                    .as_mut()
+                    .expect("No map loaded in game loop!");
+                let steps = self.physics_clock.steps_due(now);
+                let time_step = self.physics_clock.step();
+                for _ in 0..steps {
+                    step_players(&mut self.game_state.players, map, time_step);
+                }
 
-                let speedup_zones = &self
-                    .game_state
-                    .map
-                    .as_ref()
-                    .expect("No map loaded in game loop!")
-                    .speedup_zones
-                    .clone();
-
-                self.game_state.players = [0, 1, 2, 3].map(|n| {
-                    let mut player = self.game_state.players[n].do_physics_step(
-                        1.0,
-                        others(n),
-                        colliders.clone(),
-                        self.game_state
-                            .map
-                            .as_mut()
-                            .expect("No map loaded in game loop!")
-                            .trigger_iter(),
-                        speedup_zones,
-                        per_player_current_ramps.get(n).unwrap(),
-                    );
+                // anyone who's fallen off the track or gotten wedged
+                // somewhere goes back to their last checkpoint
+                for (n, player) in self.game_state.players.iter_mut().enumerate() {
+                    let position = player.entity_location.position;
+                    if let PlayerProgress::Racing { lap_info } = &mut player.placement_data {
+                        lap_info.lap_distance =
+                            map.centerline.distance_along(lap_info.zone, position);
+                    }
+
+                    if let PlayerProgress::Racing { .. } = player.placement_data {
+                        if map.is_out_of_bounds(player.entity_location.position)
+                            || player.is_stuck()
+                        {
+                            respawn_player(player, n, map, now);
+                            for connection in &mut self.connections {
+                                connection.push_outgoing(ClientBoundPacket::PlayerRespawned(n));
+                            }
+                            // which is facing the right way again
+                            self.wrong_way[n].reset();
+                            if self.connections.len() > n {
+                                self.connections[n]
+                                    .push_outgoing(ClientBoundPacket::WrongWay(false));
+                            }
+                        }
+                    }
+
+                    // and anyone who's turned around should hear about it
+                    if let Some(wrong_way) =
+                        self.wrong_way[n].update(looks_wrong_way(map, player), now)
+                    {
+                        if self.connections.len() > n {
+                            self.connections[n]
+                                .push_outgoing(ClientBoundPacket::WrongWay(wrong_way));
+                        }
+                    }
+                }
+
+                for (n, player) in self.game_state.players.iter_mut().enumerate() {
+                    let (old_powerup, old_progress, old_lap_count) = before_tick[n];
+                    if let (None, Some(powerup)) = (old_powerup, player.current_powerup) {
+                        if self.connections.len() > n {
+                            self.connections[n]
+                                .push_outgoing(ClientBoundPacket::PowerupPickup(powerup));
+                        }
+                    }
+
+                    // let them know how that lap went before anything else
+                    // about the next one
+                    if player.lap_timer.laps.len() > old_lap_count {
+                        if let (Some(lap_time), Some(best_lap)) =
+                            (player.lap_timer.laps.last(), player.lap_timer.best_lap())
+                        {
+                            let delta_to_best =
+                                player.lap_timer.previous_best_lap().map(|previous_best| {
+                                    lap_time.time.as_secs_f32() - previous_best.as_secs_f32()
+                                });
+                            if self.connections.len() > n {
+                                self.connections[n].push_outgoing(
+                                    ClientBoundPacket::LapCompleted {
+                                        lap_time: lap_time.clone(),
+                                        best_lap,
+                                        delta_to_best,
+                                    },
+                                );
+                            }
+                        }
+                    }
 
                     if let PlayerProgress::Racing {
                         lap_info: LapInformation { lap: old_lap, .. },
-                    } = self.game_state.players[n].placement_data
+                    } = old_progress
                     {
                         match player.placement_data {
                             PlayerProgress::Racing {
@@ -517,9 +743,16 @@ impl GameServer {
                     }
 
                     // Restore original player inputs: without this, the server's inputs can change multiple times per client update
-                    player.player_inputs = original_player_inputs.get(n).unwrap().to_owned();
-                    player
-                });
+                    player.player_inputs = original_player_inputs[n];
+                }
+
+                self.game_state.world_entities.update(
+                    &mut self.game_state.players,
+                    self.connections.len(),
+                    self.game_state.map.as_ref().unwrap(),
+                    now,
+                    steps as f64 * time_step,
+                );
 
                 match &mut *voting_game_state {
                     VotingState::WaitingForVotes {
@@ -543,6 +776,11 @@ impl GameServer {
                                 .unwrap_or(&&mut (0));
 
                             let decision = current_question.options[winner].clone();
+                            let targets = get_targeted_players(
+                                &decision.target,
+                                &self.game_state.players,
+                                self.connections.len(),
+                            );
                             let time_effect_is_live = Duration::new(15, 0);
                             let effect_end_time = now + time_effect_is_live;
 
@@ -575,6 +813,7 @@ impl GameServer {
                                     choice: winner,
                                     vote_effect_time: effect_end_time,
                                     option_results,
+                                    targets: targets.clone(),
                                 },
                             );
 
@@ -584,6 +823,7 @@ impl GameServer {
                                     decision: decision.clone(),
                                     time_effect_is_live,
                                     winner_idx: winner,
+                                    targets: targets.clone(),
                                 });
                             }
 
@@ -594,23 +834,29 @@ impl GameServer {
                                     change_type,
                                     expiration_time: effect_end_time,
                                 };
-                                self.game_state.players.iter_mut().for_each(|player| {
-                                    player.physics_changes.push(change.clone());
-                                });
+                                for &target in &targets {
+                                    self.game_state.players[target]
+                                        .physics_changes
+                                        .push(change.clone());
+                                }
                             }
 
                             for change in get_stats_changes_from_audience_action(
                                 &decision.action,
                                 effect_end_time,
                             ) {
-                                self.game_state.players.iter_mut().for_each(|player| {
-                                    player.stats_changes.push(change.clone());
-                                })
+                                for &target in &targets {
+                                    self.game_state.players[target]
+                                        .stats_changes
+                                        .push(change.clone());
+                                }
                             }
 
                             handle_one_time_audience_action(
                                 &decision.action,
+                                &targets,
                                 &mut self.game_state.players,
+                                &mut self.game_state.world_entities,
                             );
 
                             for (n, conn) in self.connections.iter_mut().enumerate() {
@@ -655,7 +901,10 @@ impl GameServer {
                         if *cooldown < now {
                             let time_until_vote_end = Duration::new(15, 0);
                             let vote_end_time = now + time_until_vote_end;
-                            let question: QuestionData = QUESTIONS[*question_idx].clone();
+                            let question: QuestionData = options_for_racers(
+                                &QUESTIONS[*question_idx],
+                                self.connections.len(),
+                            );
                             *question_idx = (*question_idx + 1) % QUESTIONS.len();
 
                             *voting_game_state = VotingState::WaitingForVotes {
@@ -738,13 +987,19 @@ impl GameServer {
                             time.0, time.1
                         );
                     }
+                    let lap_times =
+                        [0, 1, 2, 3].map(|n| self.game_state.players[n].lap_timer.laps.clone());
                     for conn in &mut self.connections {
                         conn.push_outgoing(ClientBoundPacket::AllDone {
                             placements: final_placement.clone(),
+                            lap_times: lap_times.clone(),
                         });
                     }
 
+                    self.audience_ledger
+                        .settle(&final_placement.map(|(place, _)| place));
                     self.game_state.phase = GamePhase::AllPlayersDone(final_placement);
+                    self.sync_audience_leaderboard();
                 }
             }
 
@@ -775,19 +1030,101 @@ impl GameServer {
     fn sync_state(&mut self) {
         match self.game_state.phase {
             // These two phases have visible players
-            GamePhase::CountingDownToGameStart(_) => self.sync_player_state(),
+            GamePhase::CountingDownToGameStart(_) => {
+                self.sync_player_state();
+                self.sync_audience_positions();
+            }
             GamePhase::PlayingGame { .. } => {
                 self.sync_player_state();
+                self.sync_drift_state();
                 self.update_and_sync_placement_state();
+                self.sync_item_boxes();
+                self.sync_audience_positions();
             }
             _ => (),
         }
 
+        self.sync_world_entities();
         self.sync_sfx_state();
+        self.sync_audience_emotes();
     }
 
-    // send placement data to each client, if its changed
-    fn update_and_sync_placement_state(&mut self) {}
+    // tell each racer how far behind the player in the place ahead of them
+    // they are, to the nearest unit, whenever that changes
+    fn update_and_sync_placement_state(&mut self) {
+        let lap_length = match &self.game_state.map {
+            Some(map) => map.centerline.lap_length(),
+            None => return,
+        };
+        let lap_count = self.lap_count();
+        let players = &self.game_state.players;
+        let placements = Self::find_player_placement(players);
+
+        let gaps = [0, 1, 2, 3].map(|n| {
+            let (_, place) = placements.iter().find(|(id, _)| *id == n)?;
+            let (ahead, _) = placements.iter().find(|(_, other)| *other + 1 == *place)?;
+            match (players[n].placement_data, players[*ahead].placement_data) {
+                (PlayerProgress::Racing { .. }, PlayerProgress::Racing { .. }) => {
+                    let gap = players[*ahead]
+                        .placement_data
+                        .race_distance(lap_length, lap_count)
+                        - players[n]
+                            .placement_data
+                            .race_distance(lap_length, lap_count);
+                    Some(gap.max(0.0).round() as f32)
+                }
+                _ => None,
+            }
+        });
+
+        for (n, connection) in self.connections.iter_mut().enumerate() {
+            if gaps[n] != self.last_gap_sync[n] {
+                connection.push_outgoing(ClientBoundPacket::GapUpdate(gaps[n]));
+            }
+        }
+        self.last_gap_sync = gaps;
+    }
+
+    // where everyone's at in the race, for the audience's standings
+    fn race_standings(&self) -> [Standing; 4] {
+        let lap_count = self.lap_count();
+        let lap_length = self
+            .game_state
+            .map
+            .as_ref()
+            .map(|map| map.centerline.lap_length())
+            .unwrap_or(0.0);
+        let race_length = lap_length * lap_count as f64;
+        let placements = Self::find_player_placement(&self.game_state.players);
+
+        [0, 1, 2, 3].map(|idx| {
+            let player = &self.game_state.players[idx];
+            let place = placements
+                .iter()
+                .find(|(id, _)| *id == idx)
+                .map(|(_, place)| *place)
+                .unwrap_or(4);
+            let progress = match player.placement_data {
+                PlayerProgress::Finished { .. } => 1.0,
+                progress if race_length > 0.0 => {
+                    progress.race_distance(lap_length, lap_count) / race_length
+                }
+                _ => 0.0,
+            };
+
+            Standing {
+                name: idx.to_string(),
+                chair: player.chair.to_string(),
+                rank: place,
+                lap: match player.placement_data {
+                    PlayerProgress::PreGame => 0,
+                    PlayerProgress::Racing { lap_info } => lap_info.lap,
+                    PlayerProgress::Finished { .. } => lap_count,
+                },
+                progress: progress.clamp(0.0, 1.0) as f32,
+            }
+        })
+    }
 
     // send player location and velocity data to every client
     fn sync_player_state(&mut self) {
@@ -811,6 +1148,78 @@ impl GameServer {
         }
     }
 
+    // let every client know who's drifting and how full their meters are,
+    // whenever that changes
+    fn sync_drift_state(&mut self) {
+        let drift_state = [0, 1, 2, 3].map(|n| {
+            let player = &self.game_state.players[n];
+            if player.player_inputs.drifting {
+                Some(player.drift_charge as f32)
+            } else {
+                None
+            }
+        });
+        if drift_state == self.last_drift_sync {
+            return;
+        }
+        self.last_drift_sync = drift_state;
+
+        for connection in &mut self.connections {
+            connection.push_outgoing(ClientBoundPacket::DriftUpdate(drift_state));
+        }
+    }
+
+    // let the audience know where everyone is, but only every so often since
+    // there could be a whole lot of them
+    fn sync_audience_positions(&mut self) {
+        let now = Instant::now();
+        if now - self.last_audience_position_sync
+            < Duration::from_millis(GLOBAL_CONFIG.audience_position_sync_ms)
+        {
+            return;
+        }
+        self.last_audience_position_sync = now;
+
+        let lap_count = self.lap_count();
+        let positions = [0, 1, 2, 3].map(|idx| {
+            let player = &self.game_state.players[idx];
+            let EntityLocation {
+                position,
+                unit_steer_direction,
+                ..
+            } = player.entity_location;
+            let (lap, zone) = match player.placement_data {
+                PlayerProgress::PreGame => (0, 0),
+                PlayerProgress::Racing { lap_info } => (lap_info.lap, lap_info.zone),
+                PlayerProgress::Finished { .. } => (lap_count, 0),
+            };
+
+            RacerPosition {
+                x: position.x as f32,
+                z: position.z as f32,
+                heading: unit_steer_direction.x.atan2(unit_steer_direction.z) as f32,
+                speed: player.velocity.length() as f32,
+                lap,
+                zone,
+            }
+        });
+
+        GameServer::broadcast_ws(
+            &mut self.ws_connections,
+            WSAudienceBoundMessage::Positions(positions),
+        );
+
+        // placements can change at any point in the race, so keep the
+        // standings fresh too
+        if let GamePhase::PlayingGame { .. } = self.game_state.phase {
+            let standings = self.race_standings();
+            GameServer::broadcast_ws(
+                &mut self.ws_connections,
+                WSAudienceBoundMessage::Standings(standings),
+            );
+        }
+    }
+
     fn sync_sfx_state(&mut self) {
         for (idx, connection) in &mut self.connections.iter_mut().enumerate() {
             for &effect in &self.game_state.players.get(idx).unwrap().sound_effects {
@@ -819,3 +1228,57 @@ impl GameServer {
         }
     }
 }
+
+// Put a player back on the track at their last checkpoint
+fn respawn_player(player: &mut PlayerEntity, player_num: PlayerID, map: &Map, now: Instant) {
+    println!("Respawning player #{}", player_num);
+    let location = map.get_respawn_location(player_num, &player.placement_data);
+    player.respawn_at(location, now);
+}
+
+// Moves every player along by one physics step of `time_step` seconds,
+// holding on to whatever sound effects they've made earlier in the tick
+fn step_players(players: &mut [PlayerEntity; 4], map: &mut Map, time_step: f64) {
+    let mut per_player_ground: Vec<GroundSample> = vec![];
+    for player in players.iter_mut() {
+        player.update_bounding_box();
+        let ground = map.ground_near(&player.reach_this_step(time_step));
+        let mut ground = player.update_upwards_from_ground(&ground, time_step);
+        ground.surface = map.surface_under(&player.bounding_box);
+        ground.boost_pad = map.boost_pad_under(&player.bounding_box);
+        player.update_surface(&ground);
+        per_player_ground.push(ground);
+    }
+
+    let stepped = [0, 1, 2, 3].map(|n| {
+        let others: Vec<&PlayerEntity> = players
+            .iter()
+            .enumerate()
+            .filter(|(other_index, _)| *other_index != n)
+            .map(|(_, player_entity)| player_entity)
+            .collect();
+        let bounds = players[n].reach_this_step(time_step);
+        let walls = map.walls_near(&bounds);
+        let speedup_zones = map.speedup_zones_near(&bounds);
+
+        let mut player = players[n].do_physics_step(
+            time_step,
+            others,
+            walls,
+            map.triggers_near(&bounds).into_iter(),
+            &speedup_zones,
+            &per_player_ground[n],
+        );
+
+        let mut sound_effects = players[n].sound_effects.clone();
+        for effect in player.sound_effects.drain(..) {
+            if !sound_effects.contains(&effect) {
+                sound_effects.push(effect);
+            }
+        }
+        player.sound_effects = sound_effects;
+        player
+    });
+
+    *players = stepped;
+}
@@ -1,16 +1,103 @@
-use crate::physics::player_entity::PlayerEntity;
+use std::time::{Duration, Instant};
+
+use chariot_core::entity_location::EntityLocation;
+use chariot_core::player::{choices::Stat, PlayerID};
+use chariot_core::world_entity::WorldEntityKind;
+use glam::DVec3;
+
+use crate::physics::{player_entity::PlayerEntity, stats_changes::StatsChange};
+
+use crate::game::world_entities::WorldEntities;
 
 use super::PowerUp;
 
-impl PowerUp {
-    pub fn _activate(&self, _activator_id: usize, _players: &[PlayerEntity; 4]) {
-        match self {
-            _ => todo!(),
+const COFFEE_DURATION: Duration = Duration::from_secs(3);
+
+// bolts launch from just in front of their user
+const SHOCK_EM_LAUNCH_DISTANCE: f64 = 1.5;
+const SHOCK_EM_LAUNCH_SPEED: f64 = 26.7;
+
+// how far behind its user a wet floor sign lands, and how hard it's tossed
+// (units per second)
+const WET_FLOOR_DROP_DISTANCE: f64 = 1.5;
+const WET_FLOOR_TOSS_SPEED: f64 = 6.67;
+
+// Finds whoever is currently sitting in a given place, if anyone
+fn find_racer_in_place(
+    place: u8,
+    players: &[PlayerEntity; 4],
+    racer_count: usize,
+) -> Option<PlayerID> {
+    players
+        .iter()
+        .take(racer_count)
+        .position(|player| player.cached_place == Some(place))
+}
+
+// Applies the powerup's effects and returns whoever it's aimed at
+pub fn activate_powerup(
+    powerup: PowerUp,
+    activator_id: PlayerID,
+    players: &mut [PlayerEntity; 4],
+    racer_count: usize,
+    world_entities: &mut WorldEntities,
+) -> Vec<PlayerID> {
+    let now = Instant::now();
+    let activator_place = players[activator_id].cached_place.unwrap_or(4);
+
+    match powerup {
+        PowerUp::Coffee => {
+            let expiration_time = now + COFFEE_DURATION;
+            let activator = &mut players[activator_id];
+            activator.stats_changes.push(StatsChange {
+                stat: Stat::MaxCarSpeed,
+                multiplier: 1.5,
+                expiration_time,
+            });
+            activator.stats_changes.push(StatsChange {
+                stat: Stat::CarAccelerator,
+                multiplier: 2.0,
+                expiration_time,
+            });
+            vec![activator_id]
         }
-    }
-    pub fn _deactivate(&self, _activator_id: usize, _players: &[PlayerEntity; 4]) {
-        match self {
-            _ => todo!(),
+        PowerUp::ShockEm => {
+            // the leader has nobody to chase, so their bolt just flies straight
+            let target = match activator_place.checked_sub(1) {
+                Some(place) => find_racer_in_place(place, players, racer_count),
+                None => None,
+            };
+            let location = players[activator_id].entity_location;
+            let forwards = location.unit_steer_direction;
+            world_entities.spawn(
+                WorldEntityKind::ShockEmBolt,
+                EntityLocation {
+                    position: location.position + forwards * SHOCK_EM_LAUNCH_DISTANCE,
+                    unit_steer_direction: forwards,
+                    unit_upward_direction: DVec3::Y,
+                },
+                forwards * SHOCK_EM_LAUNCH_SPEED,
+                Some(activator_id),
+                target,
+            );
+            target.into_iter().collect()
+        }
+        PowerUp::WetFloorSign => {
+            // nobody is affected until they run into it
+            let location = players[activator_id].entity_location;
+            let backwards = -location.unit_steer_direction;
+            world_entities.spawn(
+                WorldEntityKind::WetFloorSign,
+                EntityLocation {
+                    position: location.position + backwards * WET_FLOOR_DROP_DISTANCE,
+                    unit_steer_direction: location.unit_steer_direction,
+                    unit_upward_direction: DVec3::Y,
+                },
+                backwards * WET_FLOOR_TOSS_SPEED,
+                Some(activator_id),
+                None,
+            );
+            vec![]
         }
     }
 }
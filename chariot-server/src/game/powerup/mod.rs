@@ -1,14 +1,4 @@
 pub mod action;
 pub mod pickups;
 
-#[allow(dead_code)]
-// Powerups are not implemented yet, but the backbone/example structure is here for future reference
-#[derive(Clone, Copy)]
-pub enum PowerUp {
-    // Beneficial
-    Coffee,
-
-    // Detrimental
-    ShockEm,
-    WetFloorSign,
-}
+pub use chariot_core::powerup::PowerUp;
@@ -1,16 +1,35 @@
 use std::time::{Duration, Instant};
 
+use chariot_core::networking::ClientBoundPacket;
+use chariot_core::player::lap_info::Placement;
 use chariot_core::GLOBAL_CONFIG;
 use glam::DVec3;
+use rand::distributions::{Distribution, WeightedIndex};
 
+use crate::game::GameServer;
 use crate::physics::{
     bounding_box::BoundingBox, player_entity::PlayerEntity, trigger_entity::TriggerEntity,
 };
 
+use super::PowerUp;
+
+const POWERUPS: [PowerUp; 3] = [PowerUp::Coffee, PowerUp::ShockEm, PowerUp::WetFloorSign];
+
+// Odds of rolling each of POWERUPS, indexed by place: the further back you
+// are, the more likely you are to get something that helps you catch up
+const POWERUP_WEIGHTS_BY_PLACE: [[u32; 3]; 4] = [[1, 2, 6], [3, 3, 3], [4, 4, 2], [6, 3, 1]];
+
+fn roll_powerup(place: Placement) -> PowerUp {
+    let place_idx = (place.clamp(1, 4) - 1) as usize;
+    let distribution = WeightedIndex::new(POWERUP_WEIGHTS_BY_PLACE[place_idx])
+        .expect("powerup weights should be valid");
+    POWERUPS[distribution.sample(&mut rand::thread_rng())]
+}
+
 impl PlayerEntity {
     pub fn give_powerup(&mut self) {
-        if !self.current_powerup.is_some() {
-            // Give a powerup
+        if self.current_powerup.is_none() {
+            self.current_powerup = Some(roll_powerup(self.cached_place.unwrap_or(4)));
         }
     }
 }
@@ -19,6 +38,8 @@ impl PlayerEntity {
 pub struct ItemBox {
     pub bounds: BoundingBox,
     pub active_after: Instant,
+    // what clients were last told, so we only send them changes
+    pub shown_as_available: bool,
 }
 
 impl ItemBox {
@@ -26,8 +47,13 @@ impl ItemBox {
         Self {
             bounds,
             active_after: Instant::now(),
+            shown_as_available: true,
         }
     }
+
+    pub fn is_available(&self, now: Instant) -> bool {
+        now > self.active_after
+    }
 }
 
 impl TriggerEntity for ItemBox {
@@ -41,10 +67,32 @@ impl TriggerEntity for ItemBox {
 
     fn trigger(&mut self, player: &mut PlayerEntity) {
         // Player is only allowed to pick up if we are active
-        if !player.current_powerup.is_some() && Instant::now() > self.active_after {
+        if player.current_powerup.is_none() && self.is_available(Instant::now()) {
             player.give_powerup();
             self.active_after =
                 Instant::now() + Duration::from_secs(GLOBAL_CONFIG.powerup_cooldown_time);
         }
     }
 }
+
+impl GameServer {
+    // let everyone know about item boxes being taken or coming back
+    pub fn sync_item_boxes(&mut self) {
+        let now = Instant::now();
+        let item_boxes = match &mut self.game_state.map {
+            Some(map) => &mut map.powerups,
+            None => return,
+        };
+
+        for (idx, item_box) in item_boxes.iter_mut().enumerate() {
+            let available = item_box.is_available(now);
+            if available != item_box.shown_as_available {
+                item_box.shown_as_available = available;
+                for connection in &mut self.connections {
+                    connection
+                        .push_outgoing(ClientBoundPacket::ItemBoxAvailability(idx, available));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use chariot_core::networking::ws::{LeaderboardEntry, WSAudienceBoundMessage};
+use chariot_core::networking::Uuid;
+use chariot_core::player::lap_info::Placement;
+use chariot_core::player::PlayerID;
+use chariot_core::GLOBAL_CONFIG;
+
+use crate::game::GameServer;
+
+use super::phase::GamePhase;
+
+// A live bet on a racer winning the current race
+pub struct Bet {
+    pub bettor: Uuid,
+    pub racer: PlayerID,
+    pub wager: u32,
+    // the racer's place when the bet was made; long shots pay out more
+    pub place_when_placed: Placement,
+}
+
+// Everything the audience has staked on the current race, plus the points
+// they've built up over the whole session
+#[derive(Default)]
+pub struct AudienceLedger {
+    points: HashMap<Uuid, u32>,
+    podium_predictions: HashMap<Uuid, [PlayerID; 3]>,
+    bets: Vec<Bet>,
+}
+
+impl AudienceLedger {
+    pub fn points_of(&mut self, id: Uuid) -> u32 {
+        *self
+            .points
+            .entry(id)
+            .or_insert(GLOBAL_CONFIG.audience_starting_points)
+    }
+
+    // only the `racer_count` players actually in the race can make the podium
+    pub fn predict_podium(&mut self, id: Uuid, podium: [PlayerID; 3], racer_count: usize) -> bool {
+        let all_different =
+            podium[0] != podium[1] && podium[0] != podium[2] && podium[1] != podium[2];
+        if !all_different || podium.iter().any(|&racer| racer >= racer_count) {
+            return false;
+        }
+
+        self.podium_predictions.insert(id, podium);
+        true
+    }
+
+    // wagers are taken out of the bettor's points right away
+    pub fn place_bet(
+        &mut self,
+        id: Uuid,
+        racer: PlayerID,
+        wager: u32,
+        place_when_placed: Placement,
+    ) -> bool {
+        let available = self.points_of(id);
+        if wager == 0 || wager > available || racer >= 4 {
+            return false;
+        }
+
+        self.points.insert(id, available - wager);
+        self.bets.push(Bet {
+            bettor: id,
+            racer,
+            wager,
+            place_when_placed,
+        });
+        true
+    }
+
+    // final_placements[player_id] is that player's final place; pays out all
+    // predictions and bets and clears them for the next race
+    pub fn settle(&mut self, final_placements: &[Placement; 4]) {
+        let finisher_in = |place: Placement| -> Option<PlayerID> {
+            final_placements
+                .iter()
+                .position(|&player_place| player_place == place)
+        };
+
+        for (id, podium) in self.podium_predictions.drain() {
+            let mut earned = 0;
+            for (zero_based_place, &racer) in podium.iter().enumerate() {
+                if finisher_in((zero_based_place + 1) as Placement) == Some(racer) {
+                    earned = u32::saturating_add(
+                        earned,
+                        if zero_based_place == 0 {
+                            GLOBAL_CONFIG.prediction_winner_points
+                        } else {
+                            GLOBAL_CONFIG.prediction_podium_points
+                        },
+                    );
+                }
+            }
+
+            let points = self
+                .points
+                .entry(id)
+                .or_insert(GLOBAL_CONFIG.audience_starting_points);
+            *points = points.saturating_add(earned);
+        }
+
+        for bet in self.bets.drain(..) {
+            if finisher_in(1) == Some(bet.racer) {
+                // betting on the leader doubles your wager, betting on last place quintuples it
+                let payout = bet.wager.saturating_mul(bet.place_when_placed as u32 + 1);
+                let points = self
+                    .points
+                    .entry(bet.bettor)
+                    .or_insert(GLOBAL_CONFIG.audience_starting_points);
+                *points = points.saturating_add(payout);
+            }
+        }
+    }
+}
+
+impl GameServer {
+    pub fn predictions_are_open(&self) -> bool {
+        matches!(
+            self.game_state.phase,
+            GamePhase::ConnectingAndChoosingSettings { .. }
+                | GamePhase::WaitingForPlayerLoad { .. }
+        )
+    }
+
+    pub fn bets_are_open(&self) -> bool {
+        matches!(self.game_state.phase, GamePhase::PlayingGame { .. })
+    }
+
+    // everyone gets the same leaderboard, but their own points are
+    // highlighted and sent separately
+    pub fn sync_audience_leaderboard(&mut self) {
+        let mut standings: Vec<(Uuid, u32)> = self
+            .ws_connections
+            .keys()
+            .map(|&id| (id, self.audience_ledger.points_of(id)))
+            .collect();
+        standings.sort_by_key(|&(_, points)| Reverse(points));
+
+        for (id, connection) in self.ws_connections.iter_mut() {
+            let entries = standings
+                .iter()
+                .map(|(other_id, points)| LeaderboardEntry {
+                    name: GameServer::audience_name(other_id),
+                    points: *points,
+                    is_you: other_id == id,
+                })
+                .collect();
+
+            connection.push_outgoing_message(WSAudienceBoundMessage::Leaderboard {
+                entries,
+                points: self.audience_ledger.points_of(*id),
+            });
+        }
+    }
+
+    // we can't hand out everyone's uuid since that's what they vote with, so
+    // just give them a nickname derived from it
+    fn audience_name(id: &Uuid) -> String {
+        format!("fan-{}", &id.to_string()[..4])
+    }
+}
@@ -0,0 +1,341 @@
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+use chariot_core::networking::Uuid;
+use chariot_core::player::choices::{Chair, PlayerChoices, Track, TrackInfo};
+use chariot_core::player::lap_info::{LapInformation, LapNumber, ZoneID};
+use chariot_core::questions::{AudienceTarget, QUESTIONS};
+use chariot_core::GLOBAL_CONFIG;
+
+use crate::chairs::get_player_start_physics_properties;
+use crate::physics::player_entity::PlayerEntity;
+use crate::progress::PlayerProgress;
+
+use super::interactions::{get_targeted_players, options_for_racers};
+use super::predictions::AudienceLedger;
+use super::tracks::{display_name_from, pick_voted_track};
+use super::wrong_way::WrongWayTracker;
+
+fn get_lobby() -> [PlayerEntity; 4] {
+    [0, 1, 2, 3].map(|player_num| get_player_start_physics_properties(&Chair::Swivel, player_num))
+}
+
+#[test]
+fn test_targeting_player_beyond_racer_count() {
+    let players = get_lobby();
+
+    assert_eq!(
+        get_targeted_players(&AudienceTarget::Player(1), &players, 2),
+        vec![1]
+    );
+    assert!(get_targeted_players(&AudienceTarget::Player(2), &players, 2).is_empty());
+    assert!(get_targeted_players(&AudienceTarget::Player(3), &players, 2).is_empty());
+}
+
+#[test]
+fn test_targeting_everyone_only_means_racers() {
+    let players = get_lobby();
+
+    assert_eq!(
+        get_targeted_players(&AudienceTarget::Everyone, &players, 2),
+        vec![0, 1]
+    );
+    assert_eq!(
+        get_targeted_players(&AudienceTarget::Everyone, &players, 4),
+        vec![0, 1, 2, 3]
+    );
+}
+
+#[test]
+fn test_questions_only_offer_racers() {
+    let racer_count = 2;
+    let victim_question = QUESTIONS
+        .iter()
+        .find(|question| {
+            question
+                .options
+                .iter()
+                .any(|option| matches!(option.target, AudienceTarget::Player(_)))
+        })
+        .expect("there should be a question about a specific player");
+
+    let question = options_for_racers(victim_question, racer_count);
+
+    assert_eq!(question.options.len(), racer_count);
+    for option in &question.options {
+        match option.target {
+            AudienceTarget::Player(id) => assert!(id < racer_count),
+            _ => panic!("only players should be offered"),
+        }
+    }
+
+    // and whoever wins the vote actually gets targeted
+    let players = get_lobby();
+    for option in &question.options {
+        assert_eq!(
+            get_targeted_players(&option.target, &players, racer_count).len(),
+            1
+        );
+    }
+}
+
+#[test]
+fn test_podium_predictions_score_each_right_place() {
+    let mut ledger = AudienceLedger::default();
+    let (all_right, only_third_right) = (Uuid::new_v4(), Uuid::new_v4());
+    let starting_points = GLOBAL_CONFIG.audience_starting_points;
+
+    assert!(ledger.predict_podium(all_right, [0, 1, 2], 4));
+    assert!(ledger.predict_podium(only_third_right, [1, 0, 2], 4));
+    ledger.settle(&[1, 2, 3, 4]);
+
+    assert_eq!(
+        ledger.points_of(all_right),
+        starting_points
+            + GLOBAL_CONFIG.prediction_winner_points
+            + 2 * GLOBAL_CONFIG.prediction_podium_points
+    );
+    assert_eq!(
+        ledger.points_of(only_third_right),
+        starting_points + GLOBAL_CONFIG.prediction_podium_points
+    );
+}
+
+#[test]
+fn test_long_shot_bets_pay_out_more() {
+    let mut ledger = AudienceLedger::default();
+    let (safe_bet, long_shot, loser) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let starting_points = GLOBAL_CONFIG.audience_starting_points;
+
+    // racer 0 was leading for one bet and in last for the other, then won
+    assert!(ledger.place_bet(safe_bet, 0, 100, 1));
+    assert!(ledger.place_bet(long_shot, 0, 100, 4));
+    assert!(ledger.place_bet(loser, 1, 100, 2));
+    assert_eq!(ledger.points_of(safe_bet), starting_points - 100);
+
+    ledger.settle(&[1, 2, 3, 4]);
+
+    assert_eq!(ledger.points_of(safe_bet), starting_points + 100);
+    assert_eq!(ledger.points_of(long_shot), starting_points + 400);
+    assert_eq!(ledger.points_of(loser), starting_points - 100);
+}
+
+#[test]
+fn test_bad_wagers_are_rejected() {
+    let mut ledger = AudienceLedger::default();
+    let bettor = Uuid::new_v4();
+    let starting_points = GLOBAL_CONFIG.audience_starting_points;
+
+    assert!(!ledger.place_bet(bettor, 0, 0, 1));
+    assert!(!ledger.place_bet(bettor, 0, starting_points + 1, 1));
+    assert!(!ledger.place_bet(bettor, 4, 100, 1));
+    assert!(!ledger.predict_podium(bettor, [0, 0, 1], 4));
+    assert!(!ledger.predict_podium(bettor, [0, 1, 2], 2));
+
+    // none of those should have cost anything or paid anything out
+    ledger.settle(&[1, 2, 3, 4]);
+    assert_eq!(ledger.points_of(bettor), starting_points);
+}
+
+fn racing(lap: LapNumber, zone: ZoneID, lap_distance: f64) -> PlayerProgress {
+    PlayerProgress::Racing {
+        lap_info: LapInformation {
+            lap,
+            zone,
+            lap_distance,
+            ..LapInformation::new()
+        },
+    }
+}
+
+#[test]
+fn test_progress_orders_stages() {
+    let finished = PlayerProgress::Finished {
+        finish_time: Duration::from_secs(90),
+    };
+    let racing = racing(1, 0, 0.0);
+    let pre_game = PlayerProgress::PreGame;
+
+    assert_eq!(finished.cmp(&racing), Ordering::Less);
+    assert_eq!(racing.cmp(&pre_game), Ordering::Less);
+    assert_eq!(finished.cmp(&pre_game), Ordering::Less);
+    assert_eq!(pre_game.cmp(&finished), Ordering::Greater);
+    assert_eq!(pre_game.cmp(&PlayerProgress::PreGame), Ordering::Equal);
+}
+
+#[test]
+fn test_progress_finished_beats_any_racer() {
+    let finished = PlayerProgress::Finished {
+        finish_time: Duration::from_secs(300),
+    };
+    let far_along = racing(LapNumber::MAX, ZoneID::MAX, 1000.0);
+
+    assert_eq!(finished.cmp(&far_along), Ordering::Less);
+    assert_eq!(far_along.cmp(&finished), Ordering::Greater);
+
+    // and among finishers, whoever got there first
+    let faster = PlayerProgress::Finished {
+        finish_time: Duration::from_secs(200),
+    };
+    assert_eq!(faster.cmp(&finished), Ordering::Less);
+}
+
+#[test]
+fn test_progress_orders_racers_by_lap_then_zone_then_distance() {
+    assert_eq!(racing(2, 0, 0.0).cmp(&racing(1, 5, 90.0)), Ordering::Less);
+    assert_eq!(racing(1, 3, 10.0).cmp(&racing(1, 2, 50.0)), Ordering::Less);
+
+    // same lap and zone: whoever's further along the centerline
+    assert_eq!(racing(1, 2, 40.0).cmp(&racing(1, 2, 30.0)), Ordering::Less);
+    assert_eq!(
+        racing(1, 2, 30.0).cmp(&racing(1, 2, 40.0)),
+        Ordering::Greater
+    );
+    assert_eq!(racing(1, 2, 30.0).cmp(&racing(1, 2, 30.0)), Ordering::Equal);
+}
+
+#[test]
+fn test_wrong_way_ignores_brief_reversals() {
+    let mut tracker = WrongWayTracker::default();
+    let start = Instant::now();
+
+    assert_eq!(tracker.update(true, start), None);
+    assert_eq!(
+        tracker.update(true, start + Duration::from_millis(500)),
+        None
+    );
+    // straightening back out starts the wait over
+    assert_eq!(
+        tracker.update(false, start + Duration::from_millis(600)),
+        None
+    );
+    assert_eq!(
+        tracker.update(true, start + Duration::from_millis(1800)),
+        None
+    );
+    assert!(!tracker.wrong_way);
+}
+
+#[test]
+fn test_wrong_way_warns_then_clears() {
+    let mut tracker = WrongWayTracker::default();
+    let start = Instant::now();
+
+    assert_eq!(tracker.update(true, start), None);
+    assert_eq!(
+        tracker.update(true, start + Duration::from_millis(1500)),
+        Some(true)
+    );
+    assert_eq!(tracker.update(true, start + Duration::from_secs(5)), None);
+
+    // a moment of driving the right way isn't enough to clear it
+    let turned_around = start + Duration::from_secs(6);
+    assert_eq!(tracker.update(false, turned_around), None);
+    assert_eq!(
+        tracker.update(false, turned_around + Duration::from_millis(200)),
+        None
+    );
+    assert_eq!(
+        tracker.update(false, turned_around + Duration::from_millis(500)),
+        Some(false)
+    );
+}
+
+#[test]
+fn test_wrong_way_resets_on_respawn() {
+    let mut tracker = WrongWayTracker::default();
+    let start = Instant::now();
+
+    tracker.update(true, start);
+    tracker.update(true, start + Duration::from_millis(1500));
+    assert!(tracker.wrong_way);
+    // they'd started turning around before falling off
+    tracker.update(false, start + Duration::from_millis(1600));
+
+    tracker.reset();
+    assert!(!tracker.wrong_way);
+
+    // going the wrong way again right after respawning needs the full wait
+    let respawned = start + Duration::from_secs(3);
+    assert_eq!(tracker.update(true, respawned), None);
+    assert_eq!(
+        tracker.update(true, respawned + Duration::from_millis(1000)),
+        None
+    );
+    assert_eq!(
+        tracker.update(true, respawned + Duration::from_millis(1500)),
+        Some(true)
+    );
+}
+
+fn get_tracks() -> Vec<TrackInfo> {
+    ["alpha", "beta", "gamma"]
+        .map(|name| TrackInfo {
+            track: Track::new(name),
+            display_name: display_name_from(name),
+        })
+        .to_vec()
+}
+
+fn vote_for(name: &str) -> Option<PlayerChoices> {
+    Some(PlayerChoices {
+        map: Track::new(name),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_first_track_wins_without_votes() {
+    let tracks = get_tracks();
+
+    assert_eq!(
+        pick_voted_track(&[None, None, None, None], &tracks, 4),
+        Track::new("alpha")
+    );
+    assert_eq!(
+        pick_voted_track(&[None, None, None, None], &[], 4),
+        Track::default()
+    );
+}
+
+#[test]
+fn test_votes_for_unknown_tracks_are_ignored() {
+    let tracks = get_tracks();
+
+    assert_eq!(
+        pick_voted_track(
+            &[vote_for("deleted"), vote_for("deleted"), None, None],
+            &tracks,
+            4
+        ),
+        Track::new("alpha")
+    );
+    assert_eq!(
+        pick_voted_track(
+            &[
+                vote_for("deleted"),
+                vote_for("deleted"),
+                vote_for("beta"),
+                None
+            ],
+            &tracks,
+            4
+        ),
+        Track::new("beta")
+    );
+}
+
+#[test]
+fn test_only_connected_players_vote() {
+    let tracks = get_tracks();
+
+    // whoever used to be in the last two slots has left
+    assert_eq!(
+        pick_voted_track(
+            &[vote_for("gamma"), None, vote_for("beta"), vote_for("beta")],
+            &tracks,
+            2
+        ),
+        Track::new("gamma")
+    );
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::fs;
+
+use chariot_core::player::choices::{PlayerChoices, Track, TrackInfo};
+use chariot_core::GLOBAL_CONFIG;
+use rand::seq::SliceRandom;
+
+// "hello_world" -> "Hello World"
+pub fn display_name_from(name: &str) -> String {
+    name.split(['_', '-'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// every .glb in the tracks folder is a track; sorted so everyone sees them in
+// the same order
+pub fn discover_tracks() -> Vec<TrackInfo> {
+    let entries = match fs::read_dir(&GLOBAL_CONFIG.tracks_folder) {
+        Ok(entries) => entries,
+        Err(err) => {
+            println!(
+                "couldn't look for tracks in {}: {}",
+                GLOBAL_CONFIG.tracks_folder, err
+            );
+            return vec![];
+        }
+    };
+
+    let mut tracks: Vec<TrackInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "glb").unwrap_or(false))
+        .filter_map(|path| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(|name| TrackInfo {
+                    track: Track::new(name),
+                    display_name: display_name_from(name),
+                })
+        })
+        .collect();
+    tracks.sort_by(|a, b| a.track.name.cmp(&b.track.name));
+
+    for track in &tracks {
+        println!("found track '{}' ({})", track.display_name, track.track);
+    }
+
+    tracks
+}
+
+// The track with the most votes from connected players; ties are broken by a
+// coin flip, and if nobody voted for anything we know about, the first track
+// we found wins
+pub fn pick_voted_track(
+    player_choices: &[Option<PlayerChoices>; 4],
+    available_tracks: &[TrackInfo],
+    player_count: usize,
+) -> Track {
+    let mut votes: HashMap<&Track, usize> = HashMap::new();
+    for choices in player_choices.iter().take(player_count).flatten() {
+        if available_tracks
+            .iter()
+            .any(|info| info.track == choices.map)
+        {
+            *votes.entry(&choices.map).or_insert(0) += 1;
+        }
+    }
+
+    let most_votes = votes.values().copied().max().unwrap_or(0);
+    let tied: Vec<&Track> = available_tracks
+        .iter()
+        .map(|info| &info.track)
+        .filter(|track| votes.get(track).copied().unwrap_or(0) == most_votes)
+        .collect();
+
+    if most_votes == 0 {
+        return tied.first().map(|&track| track.clone()).unwrap_or_default();
+    }
+
+    tied.choose(&mut rand::thread_rng())
+        .map(|&track| track.clone())
+        .unwrap_or_default()
+}
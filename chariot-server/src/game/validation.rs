@@ -0,0 +1,489 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chariot_core::player::lap_info::{CheckpointID, ZoneID};
+use chariot_core::GLOBAL_CONFIG;
+use serde_json::Value;
+
+use super::map::{flatten, import_mesh, import_triangles, read_track_metadata, scene_nodes, Map};
+use crate::physics::bounding_box::BoundingBox;
+use crate::physics::ground::GroundFace;
+use crate::physics::surface::Surface;
+
+// Everything we found out about a track while reading it the same way
+// Map::load does, without giving up at the first problem
+#[derive(Default)]
+pub struct TrackReport {
+    notes: Vec<String>,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+    purpose_counts: BTreeMap<String, usize>,
+    world_bounds: Option<BoundingBox>,
+}
+
+impl TrackReport {
+    fn note(&mut self, message: String) {
+        self.notes.push(message);
+    }
+
+    fn error(&mut self, message: String) {
+        self.errors.push(message);
+    }
+
+    fn warn(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+
+    fn count(&mut self, purpose: &str) {
+        *self.purpose_counts.entry(purpose.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    pub fn print(&self) {
+        println!("meshes by purpose:");
+        for (purpose, count) in &self.purpose_counts {
+            println!("\t{}: {}", purpose, count);
+        }
+
+        if let Some(bounds) = self.world_bounds {
+            println!(
+                "world bounds: x {:.2} to {:.2}, y {:.2} to {:.2}, z {:.2} to {:.2}",
+                bounds.min_x, bounds.max_x, bounds.min_y, bounds.max_y, bounds.min_z, bounds.max_z
+            );
+        }
+
+        for note in &self.notes {
+            println!("{}", note);
+        }
+
+        for warning in &self.warnings {
+            println!("warning: {}", warning);
+        }
+        for error in &self.errors {
+            println!("error: {}", error);
+        }
+
+        println!(
+            "{} error{}, {} warning{}",
+            self.errors.len(),
+            if self.errors.len() == 1 { "" } else { "s" },
+            self.warnings.len(),
+            if self.warnings.len() == 1 { "" } else { "s" }
+        );
+    }
+}
+
+// how far apart two boxes are, or 0 if they touch
+fn distance_between(a: &BoundingBox, b: &BoundingBox) -> f64 {
+    let gap = |a_min: f64, a_max: f64, b_min: f64, b_max: f64| {
+        (b_min - a_max).max(a_min - b_max).max(0.0)
+    };
+    let dx = gap(a.min_x, a.max_x, b.min_x, b.max_x);
+    let dy = gap(a.min_y, a.max_y, b.min_y, b.max_y);
+    let dz = gap(a.min_z, a.max_z, b.min_z, b.max_z);
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+// "office" means tracks/office.glb; anything ending in .glb is taken as a path
+fn track_path(track: &str) -> String {
+    if track.ends_with(".glb") {
+        track.to_string()
+    } else {
+        format!("{}/{}.glb", GLOBAL_CONFIG.tracks_folder, track)
+    }
+}
+
+pub fn validate_track(track: &str) -> TrackReport {
+    let mut report = TrackReport::default();
+    let path = track_path(track);
+
+    if !Path::new(&path).exists() {
+        report.error(format!("{} doesn't exist", path));
+        return report;
+    }
+
+    let (document, buffers, _) = match gltf::import(&path) {
+        Ok(imported) => imported,
+        Err(err) => {
+            report.error(format!("couldn't read {} as a glb: {}", path, err));
+            return report;
+        }
+    };
+
+    if document.scenes().count() != 1 {
+        report.error(format!(
+            "track has {} scenes, but should have exactly 1",
+            document.scenes().count()
+        ));
+    }
+    let scene = match document.scenes().next() {
+        Some(scene) => scene,
+        None => return report,
+    };
+
+    let mut zones: Vec<(ZoneID, String, BoundingBox)> = vec![];
+    let mut checkpoints: Vec<(CheckpointID, String, BoundingBox)> = vec![];
+    let mut finish_lines: Vec<(String, BoundingBox)> = vec![];
+    let mut item_boxes: Vec<(String, BoundingBox)> = vec![];
+    let mut world_bounds = BoundingBox::extremes();
+
+    for (node, transform) in scene_nodes(&scene) {
+        let node_name = node.name().unwrap_or("<unnamed>").to_string();
+
+        if let Some(extras) = node.extras().as_ref() {
+            match serde_json::from_str::<Value>(extras.as_ref().get()) {
+                Ok(node_data) => {
+                    if node_data.get("purpose").and_then(Value::as_str) == Some("start_grid") {
+                        report.count("start_grid");
+                        if node_data.get("grid_slot").and_then(Value::as_u64).is_none() {
+                            report
+                                .error(format!("start grid node '{}' has no grid_slot", node_name));
+                        }
+                    }
+                }
+                Err(err) => report.error(format!(
+                    "node '{}' has extras that aren't valid JSON: {}",
+                    node_name, err
+                )),
+            }
+        }
+
+        let mesh = match node.mesh() {
+            Some(mesh) => mesh,
+            None => continue,
+        };
+        let mesh_name = mesh.name().unwrap_or("<unnamed>").to_string();
+
+        let mesh_data: Value = match mesh.extras().as_ref() {
+            Some(extras) => match serde_json::from_str(extras.as_ref().get()) {
+                Ok(mesh_data) => mesh_data,
+                Err(err) => {
+                    report.error(format!(
+                        "mesh '{}' has extras that aren't valid JSON: {}",
+                        mesh_name, err
+                    ));
+                    continue;
+                }
+            },
+            None => continue,
+        };
+        let purpose = match mesh_data.get("purpose") {
+            Some(Value::String(purpose)) => purpose.clone(),
+            _ => continue,
+        };
+
+        for primitive in mesh.primitives() {
+            let bounds = match import_mesh(&buffers, &primitive, transform) {
+                Some(bounds) => bounds,
+                None => {
+                    report.error(format!(
+                        "mesh '{}' has a primitive without positions",
+                        mesh_name
+                    ));
+                    continue;
+                }
+            };
+            world_bounds = world_bounds.accum(bounds);
+
+            match purpose.as_str() {
+                "trigger" => match mesh_data.get("trigger").and_then(Value::as_str) {
+                    Some("checkpoint") => {
+                        report.count("trigger_checkpoint");
+                        match mesh_data.get("checkpoint_id").and_then(Value::as_u64) {
+                            Some(id) => checkpoints.push((id, mesh_name.clone(), bounds)),
+                            None => report
+                                .error(format!("checkpoint '{}' has no checkpoint_id", mesh_name)),
+                        }
+                    }
+                    Some("zone") => {
+                        report.count("trigger_zone");
+                        match mesh_data.get("zone_id").and_then(Value::as_u64) {
+                            Some(id) => zones.push((id, mesh_name.clone(), bounds)),
+                            None => report.error(format!("zone '{}' has no zone_id", mesh_name)),
+                        }
+                    }
+                    Some("finish_line") => {
+                        report.count("trigger_finish_line");
+                        finish_lines.push((mesh_name.clone(), bounds));
+                    }
+                    Some("powerup") => {
+                        report.count("trigger_powerup");
+                        item_boxes.push((mesh_name.clone(), bounds));
+                    }
+                    Some(other) => report.error(format!(
+                        "mesh '{}' has unknown trigger type '{}'",
+                        mesh_name, other
+                    )),
+                    None => report.error(format!(
+                        "trigger mesh '{}' doesn't say what kind of trigger it is",
+                        mesh_name
+                    )),
+                },
+                "collision" => report.count("collision"),
+                "ground" | "ramp" => {
+                    report.count(&purpose);
+                    let triangles = match import_triangles(
+                        &mesh_name, &buffers, &primitive, transform, &bounds,
+                    ) {
+                        Ok(triangles) => triangles,
+                        Err(err) => {
+                            report.error(err.to_string());
+                            continue;
+                        }
+                    };
+                    let driveable_faces = triangles
+                        .iter()
+                        .filter(|triangle| GroundFace::is_driveable(triangle))
+                        .count();
+                    if driveable_faces == 0 {
+                        report.warn(format!(
+                            "{} '{}' is too steep to drive on anywhere, so it'll all act as a wall",
+                            purpose, mesh_name
+                        ));
+                    } else if purpose == "ramp" && bounds.max_y - bounds.min_y <= 0.0 {
+                        report.warn(format!("ramp '{}' is completely flat", mesh_name));
+                    }
+                }
+                "speedup" => report.count("speedup"),
+                "surface" => match mesh_data.get("surface").and_then(Value::as_str) {
+                    Some(surface_type) => match Surface::from_name(surface_type) {
+                        Some(_) => report.count(&format!("surface_{}", surface_type)),
+                        None => report.error(format!(
+                            "mesh '{}' has unknown surface type '{}'",
+                            mesh_name, surface_type
+                        )),
+                    },
+                    None => report.error(format!(
+                        "surface mesh '{}' doesn't say what it's made of",
+                        mesh_name
+                    )),
+                },
+                "boost_pad" => report.count("boost_pad"),
+                other => {
+                    report.count(other);
+                    report.warn(format!(
+                        "mesh '{}' has unknown purpose '{}' and will be ignored",
+                        mesh_name, other
+                    ));
+                }
+            }
+        }
+    }
+
+    if world_bounds.min_x <= world_bounds.max_x {
+        report.world_bounds = Some(world_bounds);
+    }
+
+    match read_track_metadata(&scene, &path, flatten(&world_bounds)) {
+        Ok(metadata) => report.note(format!(
+            "'{}' is {} laps long",
+            metadata.display_name, metadata.laps
+        )),
+        Err(err) => report.error(err.to_string()),
+    }
+
+    check_zones(&mut report, &mut zones);
+    check_finish_line(&mut report, &finish_lines, &zones);
+    check_checkpoints(&mut report, &mut checkpoints, &zones);
+    check_overlaps(
+        &mut report,
+        &zones,
+        &checkpoints,
+        &finish_lines,
+        &item_boxes,
+    );
+
+    // the checks above find as many problems as they can in one go, but
+    // whether the game can actually race on it is up to Map::load
+    if let Err(err) = Map::load(path) {
+        // no need to say the same thing twice
+        if !report.errors.contains(&err.to_string()) {
+            report.error(format!("the game can't load this track: {}", err));
+        }
+    }
+
+    report
+}
+
+// players start a lap in zone 0 and can only move into the next zone up, so
+// any missing id means nobody can ever finish
+fn check_zones(report: &mut TrackReport, zones: &mut [(ZoneID, String, BoundingBox)]) {
+    zones.sort_by_key(|(id, _, _)| *id);
+    let ids: Vec<ZoneID> = zones.iter().map(|(id, _, _)| *id).collect();
+
+    if ids.is_empty() {
+        report.error("track has no zones".to_string());
+        return;
+    }
+    report.note(format!("zones in order: {:?}", ids));
+
+    let last_zone = *ids.last().unwrap();
+    for expected in 1..=last_zone {
+        if !ids.contains(&expected) {
+            report.error(format!(
+                "zone {} is missing, so nobody can get past zone {}",
+                expected,
+                expected - 1
+            ));
+        }
+    }
+
+    for pair in zones.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            report.warn(format!(
+                "zone {} is made of several meshes ('{}' and '{}')",
+                pair[0].0, pair[0].1, pair[1].1
+            ));
+        }
+    }
+
+    // consecutive zones should meet, or racers have to cross no man's land
+    for pair in zones.windows(2) {
+        let ((id, name, bounds), (next_id, next_name, next_bounds)) = (&pair[0], &pair[1]);
+        if id + 1 == *next_id && !bounds.is_colliding(next_bounds) {
+            report.warn(format!(
+                "zone {} ('{}') and zone {} ('{}') are {:.2} apart",
+                id,
+                name,
+                next_id,
+                next_name,
+                distance_between(bounds, next_bounds)
+            ));
+        }
+    }
+}
+
+// a lap only counts if the finish line is crossed from the last zone
+fn check_finish_line(
+    report: &mut TrackReport,
+    finish_lines: &[(String, BoundingBox)],
+    zones: &[(ZoneID, String, BoundingBox)],
+) {
+    let (name, finish_bounds) = match finish_lines {
+        [] => {
+            report.error("track has no finish line".to_string());
+            return;
+        }
+        [finish_line] => finish_line,
+        [.., last] => {
+            report.error(format!(
+                "track has {} finish lines, and only '{}' would be used",
+                finish_lines.len(),
+                last.0
+            ));
+            last
+        }
+    };
+
+    let pos = finish_bounds.pos();
+    report.note(format!(
+        "finish line '{}' is at ({:.2}, {:.2}, {:.2})",
+        name, pos.x, pos.y, pos.z
+    ));
+
+    let last_zone = match zones.last() {
+        Some((last_zone, _, _)) => *last_zone,
+        None => return,
+    };
+    let distance = zones
+        .iter()
+        .filter(|(id, _, _)| *id == last_zone)
+        .map(|(_, _, bounds)| distance_between(bounds, finish_bounds))
+        .fold(f64::INFINITY, f64::min);
+    if distance > 0.0 {
+        report.warn(format!(
+            "finish line '{}' is {:.2} away from the last zone ({})",
+            name, distance, last_zone
+        ));
+    } else {
+        report.note(format!("finish line touches the last zone ({})", last_zone));
+    }
+}
+
+fn check_checkpoints(
+    report: &mut TrackReport,
+    checkpoints: &mut [(CheckpointID, String, BoundingBox)],
+    zones: &[(ZoneID, String, BoundingBox)],
+) {
+    checkpoints.sort_by_key(|(id, _, _)| *id);
+
+    for pair in checkpoints.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            report.error(format!(
+                "checkpoints '{}' and '{}' both have id {}",
+                pair[0].1, pair[1].1, pair[0].0
+            ));
+        } else if pair[0].0 + 1 != pair[1].0 {
+            report.warn(format!(
+                "checkpoint ids jump from {} to {}",
+                pair[0].0, pair[1].0
+            ));
+        }
+    }
+
+    for (id, name, bounds) in checkpoints.iter() {
+        if !zones
+            .iter()
+            .any(|(_, _, zone_bounds)| zone_bounds.is_colliding(bounds))
+        {
+            report.warn(format!(
+                "checkpoint {} ('{}') isn't inside any zone",
+                id, name
+            ));
+        }
+    }
+}
+
+// triggers of different kinds stacked on top of each other usually means
+// something got duplicated or dragged by accident
+fn check_overlaps(
+    report: &mut TrackReport,
+    zones: &[(ZoneID, String, BoundingBox)],
+    checkpoints: &[(CheckpointID, String, BoundingBox)],
+    finish_lines: &[(String, BoundingBox)],
+    item_boxes: &[(String, BoundingBox)],
+) {
+    let mut triggers: Vec<(String, &BoundingBox)> = vec![];
+    triggers.extend(
+        finish_lines
+            .iter()
+            .map(|(name, bounds)| (format!("finish line '{}'", name), bounds)),
+    );
+    triggers.extend(
+        item_boxes
+            .iter()
+            .map(|(name, bounds)| (format!("item box '{}'", name), bounds)),
+    );
+    triggers.extend(
+        checkpoints
+            .iter()
+            .map(|(id, name, bounds)| (format!("checkpoint {} ('{}')", id, name), bounds)),
+    );
+
+    for (i, (name, bounds)) in triggers.iter().enumerate() {
+        for (other_name, other_bounds) in triggers.iter().skip(i + 1) {
+            if bounds.is_colliding(other_bounds) {
+                report.warn(format!("{} overlaps {}", name, other_name));
+            }
+        }
+    }
+
+    // zones are meant to touch their neighbours, but not skip over one; the
+    // last zone wraps back around to the first at the finish line
+    let last_zone = zones.last().map(|(id, _, _)| *id).unwrap_or(0);
+    let are_neighbours =
+        |a: ZoneID, b: ZoneID| a.abs_diff(b) <= 1 || (a.max(b) == last_zone && a.min(b) <= 1);
+    for (i, (id, name, bounds)) in zones.iter().enumerate() {
+        for (other_id, other_name, other_bounds) in zones.iter().skip(i + 1) {
+            if !are_neighbours(*id, *other_id) && bounds.is_colliding(other_bounds) {
+                report.warn(format!(
+                    "zone {} ('{}') overlaps zone {} ('{}')",
+                    id, name, other_id, other_name
+                ));
+            }
+        }
+    }
+}
@@ -1,10 +1,8 @@
-use chariot_core::GLOBAL_CONFIG;
 use std::collections::HashMap;
 
 use chariot_core::networking::ws::{Standing, WSAudienceBoundMessage, WSServerBoundMessage};
 use chariot_core::networking::Uuid;
 use chariot_core::networking::WebSocketConnection;
-use chariot_core::player::lap_info::LapInformation;
 
 use crate::game::phase::VotingState;
 use crate::game::GameServer;
@@ -18,7 +16,13 @@ pub type AnswerID = usize;
 impl GameServer {
     // handle socket data
     pub fn process_ws_packets(&mut self) {
-        for (_id, connection) in self.ws_connections.iter_mut() {
+        let predictions_are_open = self.predictions_are_open();
+        let bets_are_open = self.bets_are_open();
+        let emotes_are_open = self.emotes_are_open();
+        let racer_count = self.connections.len();
+        let mut ledger_changed = false;
+
+        for (id, connection) in self.ws_connections.iter_mut() {
             while let Some(packet) = connection.pop_incoming() {
                 match packet {
                     WSServerBoundMessage::Vote(id, vote) => {
@@ -34,60 +38,93 @@ impl GameServer {
                             }
                         }
                     }
+                    // the ledger goes by connection too, so nobody can make
+                    // up extra accounts by sending other uuids
+                    WSServerBoundMessage::Predict(_, podium) => {
+                        if predictions_are_open
+                            && self
+                                .audience_ledger
+                                .predict_podium(*id, podium, racer_count)
+                        {
+                            println!("{} predicted a podium of {:?}", id, podium);
+                        }
+                    }
+                    // rate limited by connection, so a new uuid in every
+                    // message doesn't get around it
+                    WSServerBoundMessage::Emote(_, emote, target) => {
+                        if emotes_are_open && !matches!(target, Some(racer) if racer >= racer_count)
+                        {
+                            self.audience_emotes.record(*id, emote, target);
+                        }
+                    }
+                    WSServerBoundMessage::Bet(_, racer, wager) => {
+                        let racer_can_still_win = racer < racer_count
+                            && !matches!(
+                                self.game_state.players[racer].placement_data,
+                                PlayerProgress::Finished { .. }
+                            );
+                        if bets_are_open && racer_can_still_win {
+                            let place = self.game_state.players[racer].cached_place.unwrap_or(4);
+                            if self.audience_ledger.place_bet(*id, racer, wager, place) {
+                                println!("{} bet {} points on player {}", id, wager, racer);
+                                ledger_changed = true;
+                            }
+                        }
+                    }
                 }
             }
         }
+
+        if ledger_changed {
+            self.sync_audience_leaderboard();
+        }
     }
 
     // creates a websocket for any audience connections
     pub fn acquire_any_audience_connections(&mut self) {
-        self.ws_server
-            .set_nonblocking(true)
-            .expect("non blocking should be ok");
-
         let mut new_uuids: Vec<Uuid> = Vec::new();
 
-        if let Some(stream_result) = self.ws_server.incoming().next() {
-            if let Ok(stream) = stream_result {
-                let id = Uuid::new_v4();
-                let connection = WebSocketConnection::new(stream);
-                if let Some(connection) = connection {
-                    self.ws_connections.insert(id, connection);
-                    new_uuids.push(id);
-                    println!("acquired an audience connection!");
+        // take everyone who's waiting; handshakes finish up over the next few ticks
+        loop {
+            match self.ws_server.accept() {
+                Ok((stream, _)) => {
+                    let id = Uuid::new_v4();
+                    if let Some(connection) = WebSocketConnection::new(stream) {
+                        self.ws_connections.insert(id, connection);
+                        new_uuids.push(id);
+                        println!("acquired an audience connection!");
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    println!("couldn't accept audience connection {:?}", e);
+                    break;
                 }
             }
         }
 
-        self.ws_server
-            .set_nonblocking(false)
-            .expect("non blocking should be ok");
+        let predictions_are_open = self.predictions_are_open();
+        let race_standings = self.race_standings();
+        let has_new_connections = !new_uuids.is_empty();
 
         for id in new_uuids {
             let conn = self.ws_connections.get_mut(&id).unwrap();
 
             conn.push_outgoing_message(WSAudienceBoundMessage::Assignment(id));
+            conn.push_outgoing_message(WSAudienceBoundMessage::PredictionsOpen(
+                predictions_are_open,
+            ));
+            if let Some(map) = &self.game_state.map {
+                conn.push_outgoing_message(map.get_track_outline());
+            }
 
             if let GamePhase::PlayingGame {
                 voting_game_state, ..
             } = &mut self.game_state.phase
             {
-                conn.push_outgoing_message(WSAudienceBoundMessage::Standings([0, 1, 2, 3].map(
-                    |idx| -> Standing {
-                        Standing {
-                            name: idx.to_string(),
-                            chair: self.game_state.players[idx].chair.to_string(),
-                            rank: self.game_state.players[idx].cached_place.unwrap_or(4),
-                            lap: match self.game_state.players[idx].placement_data {
-                                PlayerProgress::PreGame => 0,
-                                PlayerProgress::Racing {
-                                    lap_info: LapInformation { lap, .. },
-                                } => lap,
-                                PlayerProgress::Finished { .. } => GLOBAL_CONFIG.number_laps,
-                            },
-                        }
-                    },
-                )));
+                conn.push_outgoing_message(WSAudienceBoundMessage::Standings(
+                    race_standings.clone(),
+                ));
 
                 if let VotingState::WaitingForVotes {
                     current_question,
@@ -115,17 +152,22 @@ impl GameServer {
                             chair: self.game_state.players[idx].chair.to_string(),
                             rank: (idx as u8) + 1,
                             lap: 0,
+                            progress: 0.0,
                         }
                     },
                 )));
             }
         }
 
-        let total_connections = self.ws_connections.len();
-        GameServer::broadcast_ws(
-            &mut self.ws_connections,
-            WSAudienceBoundMessage::AudienceCount(total_connections),
-        )
+        if has_new_connections {
+            self.sync_audience_leaderboard();
+
+            let total_connections = self.ws_connections.len();
+            GameServer::broadcast_ws(
+                &mut self.ws_connections,
+                WSAudienceBoundMessage::AudienceCount(total_connections),
+            )
+        }
     }
 
     // depending on the game state, this function will maybe get the voting state
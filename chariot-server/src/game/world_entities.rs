@@ -0,0 +1,294 @@
+use std::time::{Duration, Instant};
+
+use chariot_core::entity_location::EntityLocation;
+use chariot_core::networking::ClientBoundPacket;
+use chariot_core::player::PlayerID;
+use chariot_core::sound_effect::SoundEffect;
+use chariot_core::world_entity::{WorldEntityID, WorldEntityKind};
+use glam::DVec3;
+
+use crate::game::map::Map;
+use crate::game::GameServer;
+use crate::physics::bounding_box::BoundingBox;
+use crate::physics::oriented_box::OrientedBox;
+use crate::physics::physics_changes::{PhysicsChange, PhysicsChangeType};
+use crate::physics::player_entity::PlayerEntity;
+use crate::physics::timestep::TUNING_INTERVAL;
+
+const WET_FLOOR_SIGN_LIFETIME: Duration = Duration::from_secs(20);
+const WET_FLOOR_SIGN_SPIN_DURATION: Duration = Duration::from_secs(2);
+
+const SHOCK_EM_BOLT_LIFETIME: Duration = Duration::from_secs(5);
+const SHOCK_EM_STUN_DURATION: Duration = Duration::from_millis(1500);
+// units per second
+const SHOCK_EM_BOLT_SPEED: f64 = 26.7;
+// how sharply a bolt can turn towards its target every 30ms; 0 never turns,
+// 1 snaps straight at them
+const SHOCK_EM_HOMING_STRENGTH: f64 = 0.15;
+
+// whoever dropped something gets a moment to get out of its way
+const OWNER_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+// tossed entities slide to a stop rather than halting dead, keeping this much
+// of their speed every 30ms until they're slower than MIN_SLIDE_SPEED (units
+// per second)
+const SLIDE_FRICTION: f64 = 0.9;
+const MIN_SLIDE_SPEED: f64 = 0.33;
+
+pub struct WorldEntity {
+    pub id: WorldEntityID,
+    pub kind: WorldEntityKind,
+    pub location: EntityLocation,
+    pub velocity: DVec3,
+    pub owner: Option<PlayerID>,
+    pub target: Option<PlayerID>, // who this is chasing, if anyone
+    pub spawn_time: Instant,
+    pub expiration_time: Instant,
+    moved_since_sync: bool,
+}
+
+impl WorldEntity {
+    fn size(&self) -> DVec3 {
+        match self.kind {
+            WorldEntityKind::WetFloorSign => DVec3::new(0.8, 1.2, 0.8),
+            WorldEntityKind::ShockEmBolt => DVec3::new(0.6, 0.6, 0.6),
+        }
+    }
+
+    // entities sit on top of their position rather than around it
+    pub fn bounding_box(&self) -> BoundingBox {
+        let half_size = self.size() / 2.0;
+        let center = self.location.position + DVec3::Y * half_size.y;
+        BoundingBox::from_vecs(center - half_size, center + half_size)
+    }
+
+    fn can_hit(&self, player_id: PlayerID, now: Instant) -> bool {
+        self.owner != Some(player_id) || now - self.spawn_time > OWNER_GRACE_PERIOD
+    }
+
+    // applies this entity's effect to whoever ran into it; returns whether the
+    // entity got used up in the process
+    fn hit(&self, player: &mut PlayerEntity, now: Instant) -> bool {
+        match self.kind {
+            WorldEntityKind::WetFloorSign => {
+                player.velocity *= 0.5;
+                player.physics_changes.push(PhysicsChange {
+                    change_type: PhysicsChangeType::SpinOut,
+                    expiration_time: now + WET_FLOOR_SIGN_SPIN_DURATION,
+                });
+                player.sound_effects.push(SoundEffect::TerrainCollision);
+                true
+            }
+            WorldEntityKind::ShockEmBolt => {
+                player.velocity *= 0.3;
+                player.physics_changes.push(PhysicsChange {
+                    change_type: PhysicsChangeType::Stunned,
+                    expiration_time: now + SHOCK_EM_STUN_DURATION,
+                });
+                player.sound_effects.push(SoundEffect::PlayerCollision);
+                true
+            }
+        }
+    }
+
+    // bolts keep curving towards whoever they're chasing at a constant speed,
+    // everything else just slides to a stop
+    fn steer(&mut self, players: &[PlayerEntity; 4], time_step: f64) {
+        let intervals = time_step / TUNING_INTERVAL;
+        match self.kind {
+            WorldEntityKind::ShockEmBolt => {
+                let heading = self.velocity.normalize_or_zero();
+                let new_heading = match self.target {
+                    Some(target) => {
+                        let towards_target = (players[target].entity_location.position
+                            - self.location.position)
+                            .normalize_or_zero();
+                        let homing = 1.0 - (1.0 - SHOCK_EM_HOMING_STRENGTH).powf(intervals);
+                        heading.lerp(towards_target, homing)
+                    }
+                    None => heading,
+                }
+                .normalize_or_zero();
+
+                self.velocity = new_heading * SHOCK_EM_BOLT_SPEED;
+                if new_heading != DVec3::ZERO {
+                    self.location.unit_steer_direction = new_heading;
+                }
+            }
+            WorldEntityKind::WetFloorSign => {
+                self.velocity *= SLIDE_FRICTION.powf(intervals);
+                if self.velocity.length() < MIN_SLIDE_SPEED {
+                    self.velocity = DVec3::ZERO;
+                }
+            }
+        }
+    }
+
+    // moves along, reflecting off any walls we'd end up inside of the same
+    // way players do. Only checking where we end up would let fast bolts skip
+    // straight over thin walls, so we go in hops shorter than half our
+    // thinnest side and check after each one
+    fn move_and_bounce(&mut self, map: &Map, time_step: f64) {
+        let size = self.size();
+        let spacing = size.x.min(size.z) / 2.0;
+        let hops = (self.velocity.length() * time_step / spacing)
+            .ceil()
+            .max(1.0) as usize;
+        let hop_time = time_step / hops as f64;
+        for _ in 0..hops {
+            self.hop_and_bounce(map, hop_time);
+        }
+    }
+
+    fn hop_and_bounce(&mut self, map: &Map, time_step: f64) {
+        let old_position = self.location.position;
+        self.location.position += self.velocity * time_step;
+
+        let bounds = self.bounding_box();
+        let ours = OrientedBox::from_bounding_box(&bounds);
+        let wall_normals: Vec<DVec3> = map
+            .walls_near(&bounds)
+            .iter()
+            .filter_map(|wall| wall.contact_with_box(&ours))
+            .filter_map(|contact| {
+                DVec3::new(contact.normal.x, 0.0, contact.normal.z).try_normalize()
+            })
+            .collect();
+        if wall_normals.is_empty() {
+            return;
+        }
+
+        self.location.position = old_position;
+        for normal in wall_normals {
+            let into_wall = self.velocity.dot(normal);
+            if into_wall < 0.0 {
+                self.velocity -= 2.0 * into_wall * normal;
+            }
+        }
+    }
+}
+
+// Every server-spawned object currently in the world, plus whatever clients
+// still need to hear about them
+#[derive(Default)]
+pub struct WorldEntities {
+    next_id: WorldEntityID,
+    entities: Vec<WorldEntity>,
+    pending_packets: Vec<ClientBoundPacket>,
+}
+
+impl WorldEntities {
+    pub fn spawn(
+        &mut self,
+        kind: WorldEntityKind,
+        location: EntityLocation,
+        velocity: DVec3,
+        owner: Option<PlayerID>,
+        target: Option<PlayerID>,
+    ) -> WorldEntityID {
+        let now = Instant::now();
+        let lifetime = match kind {
+            WorldEntityKind::WetFloorSign => WET_FLOOR_SIGN_LIFETIME,
+            WorldEntityKind::ShockEmBolt => SHOCK_EM_BOLT_LIFETIME,
+        };
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.entities.push(WorldEntity {
+            id,
+            kind,
+            location,
+            velocity,
+            owner,
+            target,
+            spawn_time: now,
+            expiration_time: now + lifetime,
+            moved_since_sync: false,
+        });
+        self.pending_packets
+            .push(ClientBoundPacket::WorldEntitySpawned { id, kind, location });
+
+        id
+    }
+
+    pub fn despawn(&mut self, id: WorldEntityID) {
+        let count_before = self.entities.len();
+        self.entities.retain(|entity| entity.id != id);
+        if self.entities.len() != count_before {
+            self.pending_packets
+                .push(ClientBoundPacket::WorldEntityDespawned(id));
+        }
+    }
+
+    // moves everything along by `time_step` seconds, lets entities hit any
+    // racers touching them, and clears out whatever has expired or been used up
+    pub fn update(
+        &mut self,
+        players: &mut [PlayerEntity; 4],
+        racer_count: usize,
+        map: &Map,
+        now: Instant,
+        time_step: f64,
+    ) {
+        let mut used_up: Vec<WorldEntityID> = vec![];
+
+        for entity in &mut self.entities {
+            if entity.velocity != DVec3::ZERO {
+                entity.move_and_bounce(map, time_step);
+                entity.steer(players, time_step);
+                entity.moved_since_sync = true;
+            }
+
+            let bounds = entity.bounding_box();
+            for (player_id, player) in players.iter_mut().enumerate().take(racer_count) {
+                if entity.can_hit(player_id, now)
+                    && player.bounding_box.is_colliding(&bounds)
+                    && entity.hit(player, now)
+                {
+                    used_up.push(entity.id);
+                    break;
+                }
+            }
+
+            if entity.expiration_time < now {
+                used_up.push(entity.id);
+            }
+        }
+
+        for id in used_up {
+            self.despawn(id);
+        }
+    }
+
+    fn take_moved(&mut self) -> Vec<(WorldEntityID, EntityLocation)> {
+        self.entities
+            .iter_mut()
+            .filter(|entity| entity.moved_since_sync)
+            .map(|entity| {
+                entity.moved_since_sync = false;
+                (entity.id, entity.location)
+            })
+            .collect()
+    }
+}
+
+impl GameServer {
+    // tell every client about anything that's appeared, moved or disappeared
+    pub fn sync_world_entities(&mut self) {
+        let world_entities = &mut self.game_state.world_entities;
+        let mut packets: Vec<ClientBoundPacket> =
+            world_entities.pending_packets.drain(..).collect();
+
+        let moved = world_entities.take_moved();
+        if !moved.is_empty() {
+            packets.push(ClientBoundPacket::WorldEntityUpdate(moved));
+        }
+
+        for packet in packets {
+            for connection in &mut self.connections {
+                connection.push_outgoing(packet.clone());
+            }
+        }
+    }
+}
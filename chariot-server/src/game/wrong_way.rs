@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+use glam::DVec3;
+
+use crate::physics::{bounding_box::BoundingBox, player_entity::PlayerEntity};
+use crate::progress::PlayerProgress;
+
+use super::map::Map;
+
+// how long someone has to keep going the wrong way before we tell them, and
+// how long they have to go the right way again before we stop
+const WRONG_WAY_DELAY: Duration = Duration::from_millis(1500);
+const RIGHT_WAY_DELAY: Duration = Duration::from_millis(500);
+
+// slower than this (units per second) and it doesn't matter which way you're
+// going; you're probably just turning around or bumping into something
+const MIN_WRONG_WAY_SPEED: f64 = 2.0;
+
+// heading more than 120 degrees away from where you should be going counts
+// as going the wrong way
+const MAX_WRONG_WAY_COSINE: f64 = -0.5;
+
+// Whether we're telling a player they're going the wrong way, which only
+// changes once they've been doing the opposite for long enough that it isn't
+// just a wobble
+#[derive(Clone, Copy, Default)]
+pub struct WrongWayTracker {
+    pub wrong_way: bool,
+    // since when it's looked like the opposite of `wrong_way`
+    contradicted_since: Option<Instant>,
+}
+
+impl WrongWayTracker {
+    // Take in what the player looks like they're doing right now; if that
+    // changes what we should be telling them, returns the new state
+    pub fn update(&mut self, looks_wrong_way: bool, now: Instant) -> Option<bool> {
+        if looks_wrong_way == self.wrong_way {
+            self.contradicted_since = None;
+            return None;
+        }
+
+        let since = *self.contradicted_since.get_or_insert(now);
+        let delay = if looks_wrong_way {
+            WRONG_WAY_DELAY
+        } else {
+            RIGHT_WAY_DELAY
+        };
+        if now.saturating_duration_since(since) < delay {
+            return None;
+        }
+
+        self.wrong_way = looks_wrong_way;
+        self.contradicted_since = None;
+        Some(self.wrong_way)
+    }
+
+    // respawning puts them back facing the right way, so nothing they did
+    // beforehand should count towards a warning
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// only looking from above, since zones are about where on the track you are
+fn is_over(bounds: &BoundingBox, position: DVec3) -> bool {
+    (bounds.min_x..=bounds.max_x).contains(&position.x)
+        && (bounds.min_z..=bounds.max_z).contains(&position.z)
+}
+
+// Whether a racer looks like they're going the wrong way around the track
+// right now: either they've gone back into the zone they came from, or
+// they're driving away from the one they need to get to next
+pub fn looks_wrong_way(map: &Map, player: &PlayerEntity) -> bool {
+    let zone = match player.placement_data {
+        PlayerProgress::Racing { lap_info } => lap_info.zone,
+        _ => return false,
+    };
+    let position = player.entity_location.position;
+
+    if let Some(previous_zone) = map.previous_zone_bounds(zone) {
+        if is_over(&previous_zone, position) {
+            return true;
+        }
+    }
+
+    let velocity = DVec3::new(player.velocity.x, 0.0, player.velocity.z);
+    let speed = velocity.length();
+    if speed < MIN_WRONG_WAY_SPEED {
+        return false;
+    }
+
+    match map.next_zone_bounds(zone) {
+        Some(next_zone) => {
+            let to_next_zone = next_zone.pos() - position;
+            let to_next_zone = DVec3::new(to_next_zone.x, 0.0, to_next_zone.z);
+            match to_next_zone.try_normalize() {
+                Some(forward) => velocity.dot(forward) < MAX_WRONG_WAY_COSINE * speed,
+                // right on top of where we're going; can't be going the wrong way
+                None => false,
+            }
+        }
+        None => false,
+    }
+}
@@ -7,6 +7,17 @@ mod physics;
 mod progress;
 
 fn main() {
+    // `chariot-server validate-track <track name or .glb path>` checks a track
+    // over instead of starting a server
+    let args: Vec<String> = std::env::args().collect();
+    if let [_, command, track] = args.as_slice() {
+        if command == "validate-track" {
+            let report = game::validation::validate_track(track);
+            report.print();
+            std::process::exit(if report.has_errors() { 1 } else { 0 });
+        }
+    }
+
     // kick off the game loop
     let ip_addr = format!("0.0.0.0:{}", GLOBAL_CONFIG.port);
     game::GameServer::new(ip_addr).start_loop();
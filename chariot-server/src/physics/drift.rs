@@ -0,0 +1,83 @@
+use std::time::{Duration, Instant};
+
+use chariot_core::player::choices::Stat;
+use glam::DVec3;
+
+use super::player_entity::PlayerEntity;
+use super::stats_changes::StatsChange;
+
+// slower than this (units per second) and we're not really drifting, just
+// sitting there holding a button
+const MIN_DRIFT_SPEED: f64 = 3.0;
+
+// letting go of a drift with less than this much in the meter gets you nothing
+pub const MIN_DRIFT_CHARGE: f64 = 0.25;
+
+// how long the boost from a full drift meter lasts; partial meters get a
+// share of it
+const FULL_DRIFT_BOOST_DURATION: Duration = Duration::from_millis(1500);
+
+impl PlayerEntity {
+    // How quickly we bleed off sideways speed, in 1 / second; much less of
+    // it while drifting
+    pub fn lateral_grip(&self) -> f64 {
+        if self.player_inputs.drifting {
+            self.stat(Stat::CarGrip) * self.stat(Stat::DriftGrip)
+        } else {
+            self.stat(Stat::CarGrip)
+        }
+    }
+
+    // the part of `velocity` going across the way we're facing, along the
+    // ground
+    pub fn sideways_velocity(&self, velocity: DVec3) -> DVec3 {
+        let heading = DVec3::new(
+            self.entity_location.unit_steer_direction.x,
+            0.0,
+            self.entity_location.unit_steer_direction.z,
+        )
+        .normalize_or_zero();
+        let flat_velocity = DVec3::new(velocity.x, 0.0, velocity.z);
+        flat_velocity - heading * flat_velocity.dot(heading)
+    }
+
+    // How full the drift meter will be after drifting for `time_step` more
+    // seconds at `velocity`: the further our heading is from where we're
+    // going, the faster it fills
+    pub fn drift_charge_after(&self, velocity: DVec3, time_step: f64) -> f64 {
+        if !self.player_inputs.drifting {
+            return 0.0;
+        }
+
+        let speed = DVec3::new(velocity.x, 0.0, velocity.z).length();
+        if speed < MIN_DRIFT_SPEED {
+            return self.drift_charge;
+        }
+
+        // the sine of our drift angle, so going straight charges nothing and
+        // going fully sideways charges fastest
+        let sideways = self.sideways_velocity(velocity).length() / speed;
+
+        (self.drift_charge + self.stat(Stat::DriftChargeRate) * sideways * time_step).min(1.0)
+    }
+
+    // Turns whatever's in the drift meter into a speed boost, if there's
+    // enough of it to bother; either way the meter starts over
+    pub fn release_drift(&mut self, now: Instant) {
+        let charge = self.drift_charge;
+        self.drift_charge = 0.0;
+        if charge < MIN_DRIFT_CHARGE {
+            return;
+        }
+
+        let multiplier = 1.0 + self.stat(Stat::DriftBoost) * charge;
+        let expiration_time = now + FULL_DRIFT_BOOST_DURATION.mul_f64(charge);
+        for stat in [Stat::MaxCarSpeed, Stat::CarAccelerator] {
+            self.stats_changes.push(StatsChange {
+                stat,
+                multiplier,
+                expiration_time,
+            });
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use glam::{DQuat, DVec3};
+
+use super::{player_entity::PlayerEntity, surface::Surface, triangle::Triangle};
+
+// how far a chair's position sits above whatever it's driving on
+pub const RIDE_HEIGHT: f64 = 0.32;
+
+// how far above our position a surface can be and still be something we
+// climb onto rather than drive underneath
+pub const MAX_STEP_UP: f64 = 0.5;
+
+// faces whose normal points at least this far up are something we can drive
+// on; anything steeper than that (60 degrees) is a wall
+pub const MIN_DRIVEABLE_NORMAL_Y: f64 = 0.5;
+
+// how much faster than top speed a ramp sends you up it, in units per second
+pub const RAMP_BOOST_SPEED: f64 = 33.3;
+
+// how quickly we level back out in the air, in radians per second
+const AIR_LEVELLING_SPEED: f64 = 3.33;
+
+// One face of the track's driveable surface
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GroundFace {
+    pub triangle: Triangle,
+    // ramps give you a shove uphill while you're on them
+    pub boosts_uphill: bool,
+}
+
+// What's under a player this step
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GroundSample {
+    pub height: f64,
+    pub normal: DVec3,
+    pub boosts_uphill: bool,
+    // anything special the track's made of here, and the forward axis of
+    // any boost pad we're over; these come from the map rather than the faces
+    pub surface: Option<Surface>,
+    pub boost_pad: Option<DVec3>,
+}
+
+impl GroundFace {
+    pub fn is_driveable(triangle: &Triangle) -> bool {
+        triangle.normal().y.abs() >= MIN_DRIVEABLE_NORMAL_Y
+    }
+
+    // height of the face straight above or below (x, z), if that's over the
+    // face at all; works out where we are in the triangle as seen from above
+    fn height_at(&self, x: f64, z: f64) -> Option<f64> {
+        let [a, b, c] = self.triangle.vertices;
+        let denominator = (b.z - c.z) * (a.x - c.x) + (c.x - b.x) * (a.z - c.z);
+        if denominator.abs() < f64::EPSILON {
+            return None;
+        }
+
+        let weight_a = ((b.z - c.z) * (x - c.x) + (c.x - b.x) * (z - c.z)) / denominator;
+        let weight_b = ((c.z - a.z) * (x - c.x) + (a.x - c.x) * (z - c.z)) / denominator;
+        let weight_c = 1.0 - weight_a - weight_b;
+
+        // a little slack so we don't fall through the seams between faces
+        let slack = -1e-9;
+        if weight_a < slack || weight_b < slack || weight_c < slack {
+            return None;
+        }
+
+        Some(weight_a * a.y + weight_b * b.y + weight_c * c.y)
+    }
+
+    // the face's normal, flipped if need be so it points up out of the ground
+    fn upward_normal(&self) -> DVec3 {
+        let normal = self.triangle.normal();
+        if normal.y < 0.0 {
+            -normal
+        } else {
+            normal
+        }
+    }
+}
+
+impl GroundSample {
+    // the plain floor at y = 0 that everything off the ground meshes (and
+    // every track without any) is built on
+    pub fn flat_floor() -> GroundSample {
+        GroundSample {
+            height: 0.0,
+            normal: DVec3::Y,
+            boosts_uphill: false,
+            surface: None,
+            boost_pad: None,
+        }
+    }
+
+    // which way is up the slope, along the surface; None on flat ground
+    pub fn uphill(&self) -> Option<DVec3> {
+        (DVec3::Y - self.normal * self.normal.y).try_normalize()
+    }
+}
+
+// The highest bit of ground under (x, z) that's no higher than `reach`, so
+// bridges and other multi-level bits work out; the flat floor if there isn't
+// any ground there at all
+pub fn sample_ground(faces: &[GroundFace], x: f64, z: f64, reach: f64) -> GroundSample {
+    faces
+        .iter()
+        .filter_map(|face| face.height_at(x, z).map(|height| (face, height)))
+        .filter(|(_, height)| *height <= reach)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(face, height)| GroundSample {
+            height,
+            normal: face.upward_normal(),
+            boosts_uphill: face.boosts_uphill,
+            surface: None,
+            boost_pad: None,
+        })
+        .unwrap_or_else(GroundSample::flat_floor)
+}
+
+impl PlayerEntity {
+    // Find out what we're driving on this step, and tilt to sit flush with
+    // it; in the air we level out over `time_step` seconds instead
+    pub fn update_upwards_from_ground(
+        &mut self,
+        faces: &[GroundFace],
+        time_step: f64,
+    ) -> GroundSample {
+        let position = self.entity_location.position;
+        let reach = position.y + MAX_STEP_UP;
+        let ground = sample_ground(faces, position.x, position.z, reach);
+
+        self.entity_location.unit_upward_direction = if self.is_aerial(&ground) {
+            self.get_upward_direction_in_air(time_step)
+        } else {
+            self.get_upward_direction_on_ground(faces, &ground)
+        };
+
+        ground
+    }
+
+    // Get upward direction based on the height of the ground under each of
+    // our four corners, so we tilt smoothly over bumps and seams instead of
+    // snapping from face to face
+    fn get_upward_direction_on_ground(&self, faces: &[GroundFace], ground: &GroundSample) -> DVec3 {
+        let position = self.entity_location.position;
+        let forward = DVec3::new(
+            self.entity_location.unit_steer_direction.x,
+            0.0,
+            self.entity_location.unit_steer_direction.z,
+        )
+        .try_normalize()
+        .unwrap_or(DVec3::X);
+        let side = forward.cross(DVec3::Y);
+        let half_length = forward * self.size.x / 2.0;
+        let half_width = side * self.size.z / 2.0;
+
+        // the most the ground can rise or fall between our middle and a
+        // corner while still being the same (steepest driveable) slope
+        let max_slope = (1.0 - MIN_DRIVEABLE_NORMAL_Y.powi(2)).sqrt() / MIN_DRIVEABLE_NORMAL_Y;
+        let corner = |offset: DVec3| {
+            let corner = position + offset;
+            let max_rise = offset.length() * max_slope + 1e-6;
+            let mut height =
+                sample_ground(faces, corner.x, corner.z, ground.height + max_rise).height;
+            // a corner hanging off a ledge shouldn't tip us over the edge
+            if (height - ground.height).abs() > max_rise {
+                height = ground.height;
+            }
+            DVec3::new(corner.x, height, corner.z)
+        };
+        let front_left = corner(half_length - half_width);
+        let front_right = corner(half_length + half_width);
+        let back_left = corner(-half_length - half_width);
+        let back_right = corner(-half_length + half_width);
+
+        let diagonal_1 = front_right - back_left;
+        let diagonal_2 = front_left - back_right;
+
+        let mut upward = diagonal_1.cross(diagonal_2);
+        // when close, these can oscillate back and forth, so just make sure it's pointing positive
+        if upward.y < 0.0 {
+            upward *= -1.0;
+        }
+        upward.try_normalize().unwrap_or(ground.normal)
+    }
+
+    // whenever we're in the air, we flatten out, instead of being all wonky
+    fn get_upward_direction_in_air(&self, time_step: f64) -> DVec3 {
+        let upward = self.entity_location.unit_upward_direction;
+        if upward != DVec3::Y {
+            // this is normal to the plane which contains the Y-axis and the
+            // old upward direction; when upward_direction is equal to Y, the
+            // cross product is zero, so skip that possibility
+            let rotation_axis = DVec3::Y.cross(upward);
+            let rotation_matrix =
+                DQuat::from_axis_angle(rotation_axis, -AIR_LEVELLING_SPEED * time_step);
+
+            (rotation_matrix * upward).normalize()
+        } else {
+            DVec3::Y
+        }
+    }
+}
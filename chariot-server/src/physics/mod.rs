@@ -1,8 +1,15 @@
 pub mod bounding_box;
+pub mod drift;
+pub mod ground;
+pub mod oriented_box;
 pub mod physics_changes;
 pub mod player_entity;
-pub mod ramp;
+pub mod respawn;
+pub mod spatial_grid;
 pub mod stats_changes;
+pub mod surface;
+pub mod timestep;
+pub mod triangle;
 pub mod trigger_entity;
 
 #[cfg(test)]
@@ -0,0 +1,124 @@
+use glam::DVec3;
+
+use crate::physics::bounding_box::BoundingBox;
+use chariot_core::entity_location::EntityLocation;
+
+// below this, a cross product of two box axes means they were (nearly)
+// parallel and the axis it would give us is just noise
+const PARALLEL_EPSILON: f64 = 1e-9;
+
+// A box that turns with whatever it's attached to, unlike BoundingBox which
+// always lines up with the world axes (and so gets a lot bigger than the
+// chair whenever the chair isn't facing along one of them)
+#[derive(Copy, Clone, Debug)]
+pub struct OrientedBox {
+    pub center: DVec3,
+    // unit vectors for the box's local x, y and z; always perpendicular
+    pub axes: [DVec3; 3],
+    // half the box's size along each of the axes above
+    pub half_extents: DVec3,
+}
+
+// How two overlapping boxes touch: which way to push the first box to get it
+// out of the second, and how far it would have to go
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Contact {
+    pub normal: DVec3,
+    pub depth: f64,
+}
+
+impl OrientedBox {
+    // local x points the way the entity is steering, local y the way its top
+    // faces, same as how set_dimensions lays out the size of a chair
+    pub fn from_location(location: &EntityLocation, size: &DVec3) -> OrientedBox {
+        let up = location
+            .unit_upward_direction
+            .try_normalize()
+            .unwrap_or(DVec3::Y);
+        let steer = location.unit_steer_direction;
+        let forward = (steer - up * steer.dot(up))
+            .try_normalize()
+            .unwrap_or_else(|| up.any_orthonormal_vector());
+        let side = forward.cross(up);
+
+        OrientedBox {
+            center: location.position,
+            axes: [forward, up, side],
+            half_extents: *size / 2.0,
+        }
+    }
+
+    pub fn from_bounding_box(bounds: &BoundingBox) -> OrientedBox {
+        OrientedBox {
+            center: bounds.pos(),
+            axes: [DVec3::X, DVec3::Y, DVec3::Z],
+            half_extents: DVec3::new(
+                bounds.max_x - bounds.min_x,
+                bounds.max_y - bounds.min_y,
+                bounds.max_z - bounds.min_z,
+            ) / 2.0,
+        }
+    }
+
+    // how far the box reaches from its center when squashed onto the axis
+    pub fn projected_radius(&self, axis: DVec3) -> f64 {
+        self.half_extents.x * self.axes[0].dot(axis).abs()
+            + self.half_extents.y * self.axes[1].dot(axis).abs()
+            + self.half_extents.z * self.axes[2].dot(axis).abs()
+    }
+
+    // the point in (or on) the box nearest to `point`
+    pub fn closest_point(&self, point: DVec3) -> DVec3 {
+        let offset = point - self.center;
+        let mut closest = self.center;
+        for (axis, half_extent) in self.axes.iter().zip(self.half_extents.to_array()) {
+            closest += *axis * offset.dot(*axis).clamp(-half_extent, half_extent);
+        }
+        closest
+    }
+
+    // Separating axis test: two convex shapes don't overlap iff there's some
+    // line we can squash them both onto where they don't overlap, and for a
+    // pair of boxes we only need to try their face normals and the crosses of
+    // their edges. The axis where they overlap the least is the contact
+    // normal. Touching counts as overlapping, same as BoundingBox
+    pub fn contact_with(&self, other: &OrientedBox) -> Option<Contact> {
+        let mut candidate_axes: Vec<DVec3> = Vec::with_capacity(15);
+        candidate_axes.extend_from_slice(&self.axes);
+        candidate_axes.extend_from_slice(&other.axes);
+        for axis in self.axes {
+            for other_axis in other.axes {
+                let cross = axis.cross(other_axis);
+                if cross.length_squared() > PARALLEL_EPSILON {
+                    candidate_axes.push(cross.normalize());
+                }
+            }
+        }
+
+        let between = other.center - self.center;
+        let mut best: Option<Contact> = None;
+        for axis in candidate_axes {
+            let distance = between.dot(axis);
+            let overlap =
+                self.projected_radius(axis) + other.projected_radius(axis) - distance.abs();
+            if overlap < 0.0 {
+                return None;
+            }
+
+            let is_shallowest = match best {
+                Some(contact) => overlap < contact.depth,
+                None => true,
+            };
+            if is_shallowest {
+                // push ourselves away from wherever the other box is
+                let normal = if distance > 0.0 { -axis } else { axis };
+                best = Some(Contact {
+                    normal,
+                    depth: overlap,
+                });
+            }
+        }
+
+        best
+    }
+}
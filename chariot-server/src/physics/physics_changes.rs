@@ -5,6 +5,7 @@ use chariot_core::player::{
 use glam::DVec3;
 
 use super::player_entity::PlayerEntity;
+use super::timestep::TUNING_INTERVAL;
 
 use std::time::Instant;
 
@@ -20,6 +21,9 @@ pub enum PhysicsChangeType {
     ShoppingCart,
     SpeedBalanceBoost,
     InSpainButTheAIsSilent,
+    Stunned, // Can't drive or steer at all
+    SpinOut, // Spinning helplessly
+    Ghost,   // Just respawned; drives straight through other players
 }
 
 #[derive(Clone)]
@@ -29,7 +33,13 @@ pub struct PhysicsChange {
 }
 
 impl PlayerEntity {
+    // once per tick, before any physics steps: changes our inputs, and gives
+    // the one-off kicks that were tuned as happening once every 30ms tick (so
+    // they don't get stronger with more substeps)
     pub fn change_inputs_per_physics_changes(&mut self) {
+        // about as much spin as a step of holding down a turn used to give
+        let spin_kick = self.stat(Stat::CarSpin) * TUNING_INTERVAL;
+
         for change in &self.physics_changes {
             match change.change_type {
                 PhysicsChangeType::NoTurningRight => {
@@ -83,26 +93,26 @@ impl PlayerEntity {
                     }
                 }
 
+                PhysicsChangeType::Stunned => {
+                    self.player_inputs.engine_status = EngineStatus::Neutral;
+                    self.player_inputs.rotation_status = RotationStatus::NotInSpin;
+                }
+
+                PhysicsChangeType::SpinOut => {
+                    self.player_inputs.engine_status = EngineStatus::Neutral;
+                }
+
                 PhysicsChangeType::SpeedBalanceBoost => {
                     if self.cached_place.unwrap_or(5) != 1 {
                         self.velocity *= 1.5;
                     }
                 }
-                _ => (),
-            }
-        }
-    }
 
-    pub fn apply_physics_changes(&mut self) {
-        for change in &self.physics_changes {
-            match change.change_type {
                 PhysicsChangeType::IAmSpeed => {
+                    // the multiplier was tuned against speeds per 30ms
                     let flat_speed_increase = 30.0;
-                    self.velocity = self.velocity * (self.velocity.length() + flat_speed_increase);
-                }
-
-                PhysicsChangeType::ShoppingCart => {
-                    self.angular_velocity += self.stat(Stat::CarSpin) / 2.0;
+                    self.velocity = self.velocity
+                        * (self.velocity.length() * TUNING_INTERVAL + flat_speed_increase);
                 }
 
                 PhysicsChangeType::InSpainButTheAIsSilent => {
@@ -111,15 +121,32 @@ impl PlayerEntity {
                         RotationStatus::NotInSpin => {
                             self.player_inputs.rotation_status =
                                 RotationStatus::InSpinClockwise(1.0);
-                            self.angular_velocity += self.stat(Stat::CarSpin);
+                            self.angular_velocity += spin_kick;
                         }
                         RotationStatus::InSpinCounterclockwise(modifier) => {
                             self.player_inputs.rotation_status =
                                 RotationStatus::InSpinClockwise(modifier);
-                            self.angular_velocity += 2.0 * self.stat(Stat::CarSpin);
+                            self.angular_velocity += 2.0 * spin_kick;
                         }
                     }
                 }
+                _ => (),
+            }
+        }
+    }
+
+    // for the physics changes that keep pushing us around, over the
+    // `time_step` seconds we just simulated
+    pub fn apply_physics_changes(&mut self, time_step: f64) {
+        for change in &self.physics_changes {
+            match change.change_type {
+                PhysicsChangeType::ShoppingCart => {
+                    self.angular_velocity += self.stat(Stat::CarSpin) / 2.0 * time_step;
+                }
+
+                PhysicsChangeType::SpinOut => {
+                    self.angular_velocity += self.stat(Stat::CarSpin) * time_step;
+                }
 
                 _ => {}
             }
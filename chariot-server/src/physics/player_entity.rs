@@ -2,6 +2,8 @@ use std::time::Instant;
 
 use crate::game::powerup::PowerUp;
 use crate::physics::bounding_box::BoundingBox;
+use crate::physics::oriented_box::{Contact, OrientedBox};
+use crate::physics::triangle::Triangle;
 use chariot_core::entity_location::EntityLocation;
 use chariot_core::player::choices::{Chair, Stat};
 use chariot_core::player::lap_info::Placement;
@@ -14,15 +16,44 @@ use chariot_core::GLOBAL_CONFIG;
 use glam::{DMat3, DVec3};
 
 use crate::physics::trigger_entity::TriggerEntity;
-use crate::progress::PlayerProgress;
+use crate::progress::{LapTimer, PlayerProgress};
 
+use super::ground::{GroundSample, RAMP_BOOST_SPEED, RIDE_HEIGHT};
 use super::physics_changes::PhysicsChange;
-use super::ramp::RampCollisionResult;
 use super::stats_changes::StatsChange;
+use super::surface::{Surface, BOOST_PAD_SPEED};
+use super::timestep::TUNING_INTERVAL;
+
+// walls shorter than this (above what we're driving on) get rolled over
+const STEP_HEIGHT: f64 = 0.25;
+
+// anything slower than this (units per second) and we may as well be stopped
+const STOP_SPEED: f64 = 0.015;
+
+// how many times we halve the gap when working out exactly where along a
+// step we first touch a wall
+const SWEEP_REFINEMENTS: u32 = 10;
+
+// What a collision with another player does to us: a kick to our velocity
+// and spin, and how far to move so we stop overlapping
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlayerCollision {
+    pub delta_velocity: DVec3,
+    pub delta_angular_velocity: f64,
+    pub separation: DVec3,
+}
 
 pub struct PlayerEntity {
     pub velocity: DVec3,
-    pub angular_velocity: f64, // in radians per time unit
+    pub angular_velocity: f64, // in radians per second
+    // how full the drift meter is, from 0 to 1
+    pub drift_charge: f64,
+    // what we're driving on, if it's anything special
+    pub surface: Option<Surface>,
+    // so a boost pad only kicks us once each time we drive onto it
+    pub on_boost_pad: bool,
+    // how long we've been stopped up against a wall, in seconds
+    pub stuck_time: f64,
 
     pub size: DVec3,
     pub bounding_box: BoundingBox,
@@ -30,7 +61,7 @@ pub struct PlayerEntity {
     pub player_inputs: PlayerInputs,
     pub entity_location: EntityLocation,
 
-    pub current_colliders: Vec<BoundingBox>,
+    pub current_colliders: Vec<Triangle>,
 
     pub physics_changes: Vec<PhysicsChange>,
     pub stats_changes: Vec<StatsChange>,
@@ -39,6 +70,7 @@ pub struct PlayerEntity {
 
     pub placement_data: PlayerProgress,
     pub cached_place: Option<Placement>,
+    pub lap_timer: LapTimer,
 
     pub game_start_time: Instant,
 
@@ -74,31 +106,128 @@ impl PlayerEntity {
         );
     }
 
-    // Returns the velocity change to self from colliding with other
-    pub fn delta_v_from_collision_with_player(&self, other: &PlayerEntity) -> DVec3 {
+    // Everything this player could plausibly touch during the next physics
+    // step (of `time_step` seconds), for broad-phase lookups: wherever they
+    // are now, grown by however far they could move or turn. Deliberately
+    // generous, since velocity can still change (collisions, boosts) before
+    // they actually move
+    pub fn reach_this_step(&self, time_step: f64) -> BoundingBox {
+        let margin = self.size.length() + 2.0 * self.velocity.length() * time_step + 1.0;
+        let grow = DVec3::splat(margin);
+        BoundingBox::from_vecs(
+            self.entity_location.position - grow,
+            self.entity_location.position + grow,
+        )
+        .accum(self.bounding_box)
+    }
+
+    // the chair's actual hitbox, turned the way the chair is facing; the
+    // axis-aligned bounding_box is only good for ruling things out quickly
+    pub fn oriented_box(&self) -> OrientedBox {
+        OrientedBox::from_location(&self.entity_location, &self.size)
+    }
+
+    // Where and how deep we're touching another player, if we are at all
+    pub fn contact_with_player(&self, other: &PlayerEntity) -> Option<Contact> {
         if !self.bounding_box.is_colliding(&other.bounding_box) {
-            return DVec3::new(0.0, 0.0, 0.0);
+            return None;
+        }
+
+        self.oriented_box().contact_with(&other.oriented_box())
+    }
+
+    // Where and how deep we're touching a wall, if we are at all. Walls only
+    // push us sideways, so a contact on the top or bottom of the chair
+    // doesn't count
+    fn contact_with_terrain(&self, terrain: &Triangle) -> Option<Contact> {
+        if !self.bounding_box.is_colliding(&terrain.bounding_box()) {
+            return None;
+        }
+
+        terrain
+            .contact_with_box(&self.wall_box())
+            .and_then(flatten_contact)
+    }
+
+    // The part of the chair that can run into walls: everything but the
+    // bottom STEP_HEIGHT above what we're driving on, so we can roll over
+    // curbs and off the edges of ramps rather than snagging on them
+    fn wall_box(&self) -> OrientedBox {
+        let mut wall_box = self.oriented_box();
+        let bottom = STEP_HEIGHT - RIDE_HEIGHT;
+        let top = self.size.y / 2.0;
+        if top <= bottom {
+            return wall_box;
+        }
+
+        wall_box.center += wall_box.axes[1] * (bottom + top) / 2.0;
+        wall_box.half_extents.y = (top - bottom) / 2.0;
+        wall_box
+    }
+
+    // how hard it is to spin us around: a solid box turning about its
+    // vertical axis
+    pub fn moment_of_inertia(&self) -> f64 {
+        self.stat(Stat::Mass) * (self.size.x.powi(2) + self.size.z.powi(2)) / 12.0
+    }
+
+    // how fast a point `offset` from our center is going, what with us both
+    // moving and spinning
+    fn velocity_at(&self, offset: DVec3) -> DVec3 {
+        // positive angular velocity turns us clockwise seen from above, which
+        // is around -Y
+        self.velocity + (DVec3::Y * -self.angular_velocity).cross(offset)
+    }
+
+    // What running into other does to us, if we're touching at all. This is
+    // an impulse along the contact normal, worked out the same from either
+    // side so momentum is conserved: heavier chairs get knocked around less,
+    // and a hit away from our middle sets us spinning. We also shove
+    // ourselves our share of the way out of the other chair, with the
+    // lighter one going further; they'll do the rest on their own step
+    pub fn collision_with_player(&self, other: &PlayerEntity) -> Option<PlayerCollision> {
+        let contact = self.contact_with_player(other)?;
+        let normal = contact.normal;
+        let ours = self.oriented_box();
+        let theirs = other.oriented_box();
+
+        let our_mass = self.stat(Stat::Mass);
+        let their_mass = other.stat(Stat::Mass);
+        let separation = normal * contact.depth * their_mass / (our_mass + their_mass);
+
+        // somewhere in the middle of where the two chairs overlap
+        let contact_point =
+            (theirs.closest_point(ours.center) + ours.closest_point(theirs.center)) / 2.0;
+        let our_offset = contact_point - ours.center;
+        let their_offset = contact_point - theirs.center;
+
+        // if we're already moving apart, leave it be; the separation will
+        // finish unsticking us
+        let closing_speed =
+            (self.velocity_at(our_offset) - other.velocity_at(their_offset)).dot(normal);
+        if closing_speed >= 0.0 {
+            return Some(PlayerCollision {
+                delta_velocity: DVec3::ZERO,
+                delta_angular_velocity: 0.0,
+                separation,
+            });
         }
 
-        // Uses the angle-free equation from
-        // https://en.wikipedia.org/wiki/Elastic_collision#Two-dimensional
-        // Which applies symmetrically so it shouldn't be much of a performance
-        // hit to call this method once for each member of a colliding pair -
-        // and the formula should be fast anyways.
-
-        let v1 = self.velocity;
-        let v2 = other.velocity;
-        let m1 = self.stat(Stat::Mass);
-        let m2 = self.stat(Stat::Mass);
-        let x1 = self.entity_location.position;
-        let x2 = other.entity_location.position;
-
-        let term1 = (2.0 * m2) / (m1 + m2);
-        let term2 = (v1 - v2).dot(x1 - x2) / (x1 - x2).length_squared();
-        let term3 = x1 - x2;
-
-        let result = term1 * term2 * term3;
-        return DVec3::new(result.x, 0.0, result.z);
+        // https://en.wikipedia.org/wiki/Collision_response#Impulse-based_reaction_model
+        // but we only ever turn around the vertical axis
+        let our_turning = our_offset.cross(normal).y.powi(2) / self.moment_of_inertia();
+        let their_turning = their_offset.cross(normal).y.powi(2) / other.moment_of_inertia();
+        let restitution =
+            (self.stat(Stat::PlayerBounciness) + other.stat(Stat::PlayerBounciness)) / 2.0;
+        let impulse = -(1.0 + restitution) * closing_speed
+            / (1.0 / our_mass + 1.0 / their_mass + our_turning + their_turning);
+
+        let torque = our_offset.cross(normal * impulse);
+        Some(PlayerCollision {
+            delta_velocity: normal * impulse / our_mass,
+            delta_angular_velocity: -torque.y / self.moment_of_inertia(),
+            separation,
+        })
     }
 
     pub fn get_stat_modifier(&self, name: Stat) -> f64 {
@@ -108,6 +237,9 @@ impl PlayerEntity {
                 modifier *= change.multiplier;
             }
         }
+        if let Some(surface) = self.surface {
+            modifier *= surface.stat_multiplier(name);
+        }
         modifier
     }
 
@@ -115,71 +247,83 @@ impl PlayerEntity {
         self.get_stat_modifier(name) * self.chair.stat(&name)
     }
 
-    /* Given a set of physical properties, compute and return what next tick's
-     * physics properties will be for that object */
+    /* Given a set of physical properties, compute and return what they'll be
+     * `time_step` seconds from now */
     pub fn do_physics_step<'a>(
         &self,
         time_step: f64,
         potential_colliders: Vec<&PlayerEntity>,
-        potential_terrain: Vec<BoundingBox>,
+        potential_terrain: Vec<Triangle>,
         potential_triggers: impl Iterator<Item = &'a mut dyn TriggerEntity>,
         speedup_zones: &Vec<BoundingBox>,
-        ramp_collision_result: &RampCollisionResult,
+        ground: &GroundSample,
     ) -> PlayerEntity {
         let mut has_collided_with_players = false;
-        let minimum_player_height = match ramp_collision_result {
-            RampCollisionResult::NoEffect => 0.32,
-            RampCollisionResult::Collision { .. } => 0.32,
-            RampCollisionResult::Driveable { ramp } => {
-                ramp.get_height_at_coordinates(
-                    self.entity_location.position.x,
-                    self.entity_location.position.z,
-                ) + 0.50
-            }
-        };
+        let minimum_player_height = ground.height + RIDE_HEIGHT;
 
-        let self_forces = self.sum_of_self_forces(ramp_collision_result);
+        let self_forces = self.sum_of_self_forces(ground);
         let acceleration = self_forces / self.stat(Stat::Mass);
 
-        let angular_velocity: f64 = match self.player_inputs.rotation_status {
+        let mut angular_velocity: f64 = match self.player_inputs.rotation_status {
             RotationStatus::InSpinClockwise(modifier) => f64::min(
                 modifier as f64 * self.stat(Stat::MaxCarSpin),
-                self.angular_velocity + modifier as f64 * self.stat(Stat::CarSpin),
+                self.angular_velocity + modifier as f64 * self.stat(Stat::CarSpin) * time_step,
             ),
             RotationStatus::InSpinCounterclockwise(modifier) => f64::max(
                 -modifier as f64 * self.stat(Stat::MaxCarSpin),
-                self.angular_velocity - modifier as f64 * self.stat(Stat::CarSpin),
+                self.angular_velocity - modifier as f64 * self.stat(Stat::CarSpin) * time_step,
             ),
             RotationStatus::NotInSpin => {
-                self.angular_velocity * self.stat(Stat::RotationReductionCoefficient)
+                self.angular_velocity
+                    * self
+                        .stat(Stat::RotationReductionCoefficient)
+                        .powf(time_step / TUNING_INTERVAL)
             }
         };
 
-        let rotation_matrix = DMat3::from_axis_angle(DVec3::Y, -1.0 * angular_velocity);
-
         let mut delta_velocity = acceleration * time_step;
 
-        for collider in potential_colliders.iter() {
-            let delta_v = self.delta_v_from_collision_with_player(collider);
-            delta_velocity += self.stat(Stat::PlayerBounciness) * delta_v;
-            if delta_v != DVec3::ZERO {
-                has_collided_with_players = true;
+        // how far to shove ourselves so we stop overlapping other players
+        let mut separation = DVec3::ZERO;
+
+        // nobody bumps into someone who's just respawned
+        for collider in potential_colliders
+            .iter()
+            .filter(|collider| !self.is_ghost() && !collider.is_ghost())
+        {
+            if let Some(collision) = self.collision_with_player(collider) {
+                delta_velocity += collision.delta_velocity;
+                angular_velocity += collision.delta_angular_velocity;
+                separation += collision.separation;
+                if collision.delta_velocity != DVec3::ZERO {
+                    has_collided_with_players = true;
+                }
             }
         }
 
-        let mut terrain_with_collisions = potential_terrain.clone();
-        terrain_with_collisions.retain(|terrain| self.bounding_box.is_colliding(terrain));
-        if let RampCollisionResult::Collision { ramp } = ramp_collision_result {
-            terrain_with_collisions.push(ramp.bounding_box());
+        let mut terrain_with_collisions: Vec<Triangle> = vec![];
+        let mut terrain_contacts: Vec<Contact> = vec![];
+        for terrain in &potential_terrain {
+            if let Some(contact) = self.contact_with_terrain(terrain) {
+                terrain_with_collisions.push(*terrain);
+                terrain_contacts.push(contact);
+            }
         }
         let collision_terrain_is_new = terrain_with_collisions != self.current_colliders;
 
         // Make sure we aren't too fast/slow, but BEFORE we bounce off walls or accelerate off ramps(which can be fast intentionally)
         // 1. velocity changes from bouncing off walls
         let mut new_velocity = self.velocity + delta_velocity;
+        // our wheels only want to go the way we're facing, so we lose some
+        // of however fast we're sliding sideways (a lot less of it while
+        // drifting); in the air there's nothing to grip
+        if !self.is_aerial(ground) {
+            let grip = 1.0 - (-self.lateral_grip() * time_step).exp();
+            new_velocity -= self.sideways_velocity(new_velocity) * grip;
+        }
         if new_velocity.length() > self.stat(Stat::MaxCarSpeed) {
             new_velocity = new_velocity.normalize() * self.stat(Stat::MaxCarSpeed);
-        } else if new_velocity.length() < 0.0005 {
+        } else if new_velocity.length() < STOP_SPEED {
             new_velocity = DVec3::ZERO;
         } else if new_velocity.dot(self.velocity) < 0.0 {
             // If we are trying to reverse direction and are braking, we should just stop isntead
@@ -189,65 +333,97 @@ impl PlayerEntity {
         }
 
         // 2. velocity changes from ramp-zooming
-        if let RampCollisionResult::Driveable { ramp } = ramp_collision_result {
-            if !self.is_aerial(ramp_collision_result) {
-                let ramp_incline = ramp.get_incline_vector().normalize();
-
+        if ground.boosts_uphill && !self.is_aerial(ground) {
+            if let Some(ramp_incline) = ground.uphill() {
                 // vroom vroom vroom
-                new_velocity += ramp_incline;
+                new_velocity += ramp_incline * RAMP_BOOST_SPEED;
             }
         }
 
-        // We only react to colliding with a set of objects if we aren't already
-        // colliding with them (otherwise, it's super easy to get stuck inside
-        // an object)
-        if collision_terrain_is_new {
-            let multiplier = -(1.0 + self.stat(Stat::TerrainBounciness));
-            for terrain in &terrain_with_collisions {
-                // We want to "reflect" off of objects: this means negating the
-                // x component of velocity if hitting a face parallel to the
-                // z-axis, and vice versa. But if we're already going away from
-                // an object, we don't want to change that direction of
-                // velocity.
-                let x = self.entity_location.position.x;
-                let z = self.entity_location.position.z;
-
-                if x >= terrain.min_x && x <= terrain.max_x {
-                    if (z < terrain.max_z && self.velocity.z > 0.0)
-                        || (z > terrain.min_z && self.velocity.z < 0.0)
-                    {
-                        new_velocity.z += multiplier * self.velocity.z;
-                    }
-                } else {
-                    if (x < terrain.max_x && self.velocity.x > 0.0)
-                        || (x > terrain.min_x && self.velocity.x < 0.0)
-                    {
-                        new_velocity.x += multiplier * self.velocity.x;
-                    }
-                }
+        // 2b. velocity changes from boost pads, which only kick us once as
+        // we drive onto them
+        let boost_pad = ground.boost_pad.filter(|_| !self.is_aerial(ground));
+        let on_boost_pad = boost_pad.is_some();
+        let hit_boost_pad = on_boost_pad && !self.on_boost_pad;
+        if let Some(forward) = boost_pad.filter(|_| hit_boost_pad) {
+            new_velocity += forward * BOOST_PAD_SPEED;
+        }
+
+        // 3. velocity changes from bouncing off walls: we "reflect" the part
+        // of our velocity heading into each face we're touching and keep the
+        // part sliding along it. If we're already going away from a face we
+        // leave it be, and we get shoved back out of whatever we're inside of
+        // so we can't end up stuck in it
+        let multiplier = -(1.0 + self.stat(Stat::TerrainBounciness));
+        let mut terrain_correction = DVec3::ZERO;
+        let mut bounced_off_terrain = false;
+        for contact in &terrain_contacts {
+            let into_terrain = new_velocity.dot(contact.normal);
+            if into_terrain < 0.0 {
+                new_velocity += multiplier * into_terrain * contact.normal;
+                bounced_off_terrain = true;
+            }
+
+            // a flat wall is usually a couple of triangles that all want to
+            // push us the same way, so only push as far as still needed
+            let already_pushed = terrain_correction.dot(contact.normal);
+            if contact.depth > already_pushed {
+                terrain_correction += contact.normal * (contact.depth - already_pushed);
             }
         }
 
-        // If not in contact with any speedup zones (= in the air or off-track), apply a speed penalty
-        if !self.is_aerial(ramp_collision_result)
+        // If not in contact with any speedup zones (= in the air or off-track), apply a speed penalty;
+        // special surfaces and boost pads count as track, they have their own effects
+        if !self.is_aerial(ground)
+            && self.surface.is_none()
+            && !on_boost_pad
             && speedup_zones
                 .iter()
                 .all(|zone| !zone.is_colliding(&self.bounding_box))
         {
-            new_velocity *= 1.0 - GLOBAL_CONFIG.off_track_speed_penalty;
+            new_velocity *=
+                (1.0 - GLOBAL_CONFIG.off_track_speed_penalty).powf(time_step / TUNING_INTERVAL);
         }
 
+        let rotation_matrix = DMat3::from_axis_angle(DVec3::Y, -1.0 * angular_velocity * time_step);
+
         let new_steer_direction =
             rotation_matrix * self.entity_location.unit_steer_direction.normalize();
 
-        let mut new_position = self.entity_location.position + self.velocity * time_step;
+        // we don't keep driving into walls we're already touching, just
+        // slide along them; then make sure we don't go through any new ones
+        // on the way
+        let mut movement = self.velocity;
+        for contact in &terrain_contacts {
+            let into_terrain = movement.dot(contact.normal);
+            if into_terrain < 0.0 {
+                movement -= into_terrain * contact.normal;
+            }
+        }
+        let movement = self.sweep_through_terrain(
+            &potential_terrain,
+            &terrain_with_collisions,
+            movement * time_step + separation + terrain_correction,
+        );
+
+        let mut new_position = self.entity_location.position + movement;
         if new_position.y < minimum_player_height {
             new_position.y = minimum_player_height;
         }
 
+        let stuck_time =
+            self.stuck_time_after(movement, !terrain_with_collisions.is_empty(), time_step);
+
+        // the drift meter only fills while we're on the ground
+        let drift_charge = if self.is_aerial(ground) {
+            self.drift_charge
+        } else {
+            self.drift_charge_after(new_velocity, time_step)
+        };
+
         let mut sound_effects = vec![];
 
-        if collision_terrain_is_new {
+        if collision_terrain_is_new && bounced_off_terrain {
             sound_effects.push(SoundEffect::TerrainCollision);
         }
         if has_collided_with_players {
@@ -258,6 +434,7 @@ impl PlayerEntity {
             player_inputs: PlayerInputs {
                 engine_status: self.player_inputs.engine_status,
                 rotation_status: self.player_inputs.rotation_status,
+                drifting: self.player_inputs.drifting,
             },
 
             entity_location: EntityLocation {
@@ -270,6 +447,10 @@ impl PlayerEntity {
 
             velocity: new_velocity,
             angular_velocity,
+            drift_charge,
+            surface: self.surface,
+            on_boost_pad,
+            stuck_time,
             size: self.size,
             bounding_box: self.bounding_box,
             physics_changes: self.physics_changes.clone(),
@@ -280,9 +461,14 @@ impl PlayerEntity {
             game_start_time: self.game_start_time,
             placement_data: self.placement_data,
             cached_place: self.cached_place,
+            lap_timer: self.lap_timer.clone(),
         };
 
-        new_player.apply_physics_changes();
+        new_player.apply_physics_changes(time_step);
+
+        if hit_boost_pad {
+            new_player.boost_from_pad(Instant::now());
+        }
 
         for trigger in potential_triggers {
             if trigger
@@ -296,35 +482,84 @@ impl PlayerEntity {
         return new_player;
     }
 
-    fn is_aerial(&self, ramp_collision_result: &RampCollisionResult) -> bool {
-        let ground_level = match ramp_collision_result {
-            RampCollisionResult::NoEffect => 0.0,
-            RampCollisionResult::Collision { ramp } | RampCollisionResult::Driveable { ramp } => {
-                ramp.get_height_at_coordinates(
-                    self.entity_location.position.x,
-                    self.entity_location.position.z,
-                )
-            }
+    // How much of `movement` we can make before running into a wall we
+    // weren't already touching. Only checking where we end up would let fast
+    // chairs skip straight over thin walls, so we also check along the way,
+    // closer together than the chair is thick, and stop as soon as we touch
+    // something new that's in our way
+    fn sweep_through_terrain(
+        &self,
+        potential_terrain: &[Triangle],
+        already_touching: &[Triangle],
+        movement: DVec3,
+    ) -> DVec3 {
+        let start = self.wall_box();
+        let end = start.center + movement;
+        let reach = DVec3::splat(start.half_extents.length());
+        let swept_bounds =
+            BoundingBox::from_vecs(start.center.min(end) - reach, start.center.max(end) + reach);
+        let candidates: Vec<&Triangle> = potential_terrain
+            .iter()
+            .filter(|terrain| !already_touching.contains(terrain))
+            .filter(|terrain| terrain.bounding_box().is_colliding(&swept_bounds))
+            .collect();
+        if candidates.is_empty() {
+            return movement;
+        }
+
+        let is_blocked_at = |fraction: f64| {
+            let mut moved = start;
+            moved.center += movement * fraction;
+            candidates.iter().any(|terrain| {
+                match terrain.contact_with_box(&moved).and_then(flatten_contact) {
+                    Some(contact) => contact.normal.dot(movement) < 0.0,
+                    None => false,
+                }
+            })
         };
 
-        self.entity_location.position[1] - 1.0 > ground_level
+        // half the chair's thinnest side, so nothing thin can fit between
+        // two checks
+        let spacing = start.half_extents.x.min(start.half_extents.z);
+        let checks = (movement.length() / spacing).ceil().max(1.0) as usize;
+
+        let mut clear = 0.0;
+        for check in 1..=checks {
+            let fraction = check as f64 / checks as f64;
+            if is_blocked_at(fraction) {
+                // narrow it down to just where we first touch, and stop
+                // there; next step will bounce us off it like any other wall
+                let mut blocked = fraction;
+                for _ in 0..SWEEP_REFINEMENTS {
+                    let middle = (clear + blocked) / 2.0;
+                    if is_blocked_at(middle) {
+                        blocked = middle;
+                    } else {
+                        clear = middle;
+                    }
+                }
+                return movement * blocked;
+            }
+            clear = fraction;
+        }
+
+        movement
     }
 
-    fn sum_of_self_forces(&self, ramp_collision_result: &RampCollisionResult) -> DVec3 {
+    pub fn is_aerial(&self, ground: &GroundSample) -> bool {
+        self.entity_location.position[1] - 1.0 > ground.height
+    }
+
+    fn sum_of_self_forces(&self, ground: &GroundSample) -> DVec3 {
         let gravitational_force = self.gravitational_force_on_object();
         let air_forces = gravitational_force
             + self.player_applied_force_on_object()
             + self.air_resistance_force_on_object();
 
-        let mut normal_force = self.normal_force_on_object();
-        if normal_force.length_squared() > gravitational_force.length_squared() {
-            normal_force = normal_force * gravitational_force.length() / normal_force.length();
-        }
-
-        return if self.is_aerial(ramp_collision_result) {
+        return if self.is_aerial(ground) {
             air_forces
         } else {
-            air_forces + normal_force + self.rolling_resistance_force_on_object()
+            air_forces + self.normal_force_on_object() + self.rolling_resistance_force_on_object()
         };
     }
 
@@ -334,8 +569,11 @@ impl PlayerEntity {
             * self.stat(Stat::GravityCoefficient);
     }
 
+    // as hard as gravity pulls us into the ground, straight out of it
     fn normal_force_on_object(&self) -> DVec3 {
-        return self.entity_location.unit_upward_direction * self.stat(Stat::Mass);
+        return self.entity_location.unit_upward_direction
+            * self.stat(Stat::Mass)
+            * self.stat(Stat::GravityCoefficient);
     }
 
     fn acceleration_force_on_object(&self) -> DVec3 {
@@ -391,3 +629,13 @@ impl PlayerEntity {
             * self.stat(Stat::RollingResistanceCoefficient);
     }
 }
+
+// A contact flattened onto the ground plane, or None if it's straight up
+// or down
+fn flatten_contact(contact: Contact) -> Option<Contact> {
+    let normal = DVec3::new(contact.normal.x, 0.0, contact.normal.z).try_normalize()?;
+    Some(Contact {
+        normal,
+        depth: contact.depth,
+    })
+}
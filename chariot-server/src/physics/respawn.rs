@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+use chariot_core::entity_location::EntityLocation;
+use chariot_core::GLOBAL_CONFIG;
+use glam::DVec3;
+
+use super::physics_changes::{PhysicsChange, PhysicsChangeType};
+use super::player_entity::PlayerEntity;
+
+// moving slower than this (units per second) while touching a wall counts as
+// being stuck against it
+const STUCK_SPEED: f64 = 0.5;
+
+impl PlayerEntity {
+    // whether we just respawned and shouldn't bump into anyone yet
+    pub fn is_ghost(&self) -> bool {
+        self.physics_changes
+            .iter()
+            .any(|change| matches!(change.change_type, PhysicsChangeType::Ghost))
+    }
+
+    // How long we'll have been stuck after moving `movement` in `time_step`
+    // seconds: any time we're up against a wall and not really getting
+    // anywhere adds up, and getting going again starts it over
+    pub fn stuck_time_after(&self, movement: DVec3, touching_walls: bool, time_step: f64) -> f64 {
+        if touching_walls && movement.length() < STUCK_SPEED * time_step {
+            self.stuck_time + time_step
+        } else {
+            0.0
+        }
+    }
+
+    // whether we've been stuck long enough that we should be put back on the
+    // track
+    pub fn is_stuck(&self) -> bool {
+        self.stuck_time >= GLOBAL_CONFIG.respawn_stuck_ms as f64 / 1000.0
+    }
+
+    // Put us back at `location` at a standstill, and let us drive through
+    // everyone for a bit so we don't land on top of anyone
+    pub fn respawn_at(&mut self, location: EntityLocation, now: Instant) {
+        self.entity_location = location;
+        self.velocity = DVec3::ZERO;
+        self.angular_velocity = 0.0;
+        self.drift_charge = 0.0;
+        self.stuck_time = 0.0;
+        self.current_colliders.clear();
+        self.on_boost_pad = false;
+        self.update_bounding_box();
+
+        self.physics_changes.push(PhysicsChange {
+            change_type: PhysicsChangeType::Ghost,
+            expiration_time: now + Duration::from_millis(GLOBAL_CONFIG.respawn_ghost_ms),
+        });
+    }
+}
@@ -0,0 +1,133 @@
+use crate::physics::bounding_box::BoundingBox;
+
+// keeps huge tracks from turning into millions of mostly-empty cells
+const MAX_CELLS_PER_SIDE: usize = 256;
+
+// A uniform grid over the ground plane that remembers which boxes overlap
+// each cell, so we only have to look at things near a player instead of
+// everything on the track. Built once per map; the boxes themselves live
+// wherever the caller keeps them, we just hand back their indices
+pub struct SpatialGrid {
+    min_x: f64,
+    min_z: f64,
+    cell_size: f64,
+    cols: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl SpatialGrid {
+    pub fn new(boxes: &[BoundingBox], cell_size: f64) -> SpatialGrid {
+        let bounds = boxes
+            .iter()
+            .fold(BoundingBox::extremes(), |acc, bounds| acc.accum(*bounds));
+
+        if boxes.is_empty() {
+            return SpatialGrid {
+                min_x: 0.0,
+                min_z: 0.0,
+                cell_size,
+                cols: 0,
+                rows: 0,
+                cells: vec![],
+            };
+        }
+
+        let width = bounds.max_x - bounds.min_x;
+        let depth = bounds.max_z - bounds.min_z;
+        let cell_size = cell_size.max(width.max(depth) / MAX_CELLS_PER_SIDE as f64);
+        let cols = (width / cell_size).floor() as usize + 1;
+        let rows = (depth / cell_size).floor() as usize + 1;
+
+        let mut grid = SpatialGrid {
+            min_x: bounds.min_x,
+            min_z: bounds.min_z,
+            cell_size,
+            cols,
+            rows,
+            cells: vec![vec![]; cols * rows],
+        };
+
+        for (index, bounds) in boxes.iter().enumerate() {
+            if let Some((col_range, row_range)) = grid.cell_ranges(bounds) {
+                for row in row_range {
+                    for col in col_range.clone() {
+                        grid.cells[row * cols + col].push(index);
+                    }
+                }
+            }
+        }
+
+        grid
+    }
+
+    fn cell_ranges(
+        &self,
+        bounds: &BoundingBox,
+    ) -> Option<(
+        std::ops::RangeInclusive<usize>,
+        std::ops::RangeInclusive<usize>,
+    )> {
+        if self.cells.is_empty() {
+            return None;
+        }
+
+        let to_cell = |value: f64, min: f64, count: usize| -> Option<usize> {
+            let cell = ((value - min) / self.cell_size).floor();
+            if cell < 0.0 {
+                None
+            } else {
+                Some((cell as usize).min(count - 1))
+            }
+        };
+
+        // anything entirely off one side of the grid can't touch anything in it
+        let max_col = to_cell(bounds.max_x, self.min_x, self.cols)?;
+        let max_row = to_cell(bounds.max_z, self.min_z, self.rows)?;
+        let max_x = self.min_x + self.cols as f64 * self.cell_size;
+        let max_z = self.min_z + self.rows as f64 * self.cell_size;
+        if bounds.min_x > max_x || bounds.min_z > max_z {
+            return None;
+        }
+        let min_col = to_cell(bounds.min_x, self.min_x, self.cols).unwrap_or(0);
+        let min_row = to_cell(bounds.min_z, self.min_z, self.rows).unwrap_or(0);
+
+        Some((min_col..=max_col, min_row..=max_row))
+    }
+
+    // indices of every box sharing a cell with the given bounds, in ascending
+    // order and without repeats; callers still need to do the real overlap
+    // test, this just rules out everything far away
+    pub fn query(&self, bounds: &BoundingBox) -> Vec<usize> {
+        let mut found = vec![];
+        if let Some((col_range, row_range)) = self.cell_ranges(bounds) {
+            for row in row_range {
+                for col in col_range.clone() {
+                    found.extend_from_slice(&self.cells[row * self.cols + col]);
+                }
+            }
+        }
+
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+
+// Mutable references to the items at the given ascending, unique indices,
+// without walking the whole slice
+pub fn pick_mut<'a, T>(items: &'a mut [T], sorted_indices: &[usize]) -> Vec<&'a mut T> {
+    let mut picked = Vec::with_capacity(sorted_indices.len());
+    let mut rest = items;
+    let mut consumed = 0;
+
+    for &index in sorted_indices {
+        let (_, tail) = rest.split_at_mut(index - consumed);
+        let (item, tail) = tail.split_first_mut().unwrap();
+        picked.push(item);
+        rest = tail;
+        consumed = index + 1;
+    }
+
+    picked
+}
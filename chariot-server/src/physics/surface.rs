@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+use chariot_core::player::choices::Stat;
+use glam::DVec3;
+
+use super::bounding_box::BoundingBox;
+use super::ground::GroundSample;
+use super::player_entity::PlayerEntity;
+use super::stats_changes::StatsChange;
+
+// how much speed a boost pad adds along its forward axis, in units per second
+pub const BOOST_PAD_SPEED: f64 = 16.7;
+
+// for a little while after a boost pad our top speed goes up by this much, so
+// we actually get to keep the speed it gave us
+pub const BOOST_PAD_TOP_SPEED_MULTIPLIER: f64 = 1.5;
+const BOOST_PAD_DURATION: Duration = Duration::from_millis(1000);
+
+// What a patch of the track is made of, which changes how chairs handle while
+// they're on it
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Surface {
+    Ice,
+    Mud,
+    Carpet,
+}
+
+impl Surface {
+    // what track authors call it in the mesh's "surface" property
+    pub fn from_name(name: &str) -> Option<Surface> {
+        match name {
+            "ice" => Some(Surface::Ice),
+            "mud" => Some(Surface::Mud),
+            "carpet" => Some(Surface::Carpet),
+            _ => None,
+        }
+    }
+
+    // how much this surface scales a stat by while we're on it
+    pub fn stat_multiplier(&self, stat: Stat) -> f64 {
+        match (self, stat) {
+            // slippery: hard to get going, hard to stop, hard to turn
+            (Surface::Ice, Stat::CarGrip) => 0.1,
+            (Surface::Ice, Stat::RollingResistanceCoefficient) => 0.2,
+            (Surface::Ice, Stat::CarAccelerator) => 0.5,
+            (Surface::Ice, Stat::CarBrake) => 0.3,
+            // sticky and slow
+            (Surface::Mud, Stat::MaxCarSpeed) => 0.5,
+            (Surface::Mud, Stat::RollingResistanceCoefficient) => 3.0,
+            // a bit slower than the floor, but grippy
+            (Surface::Carpet, Stat::MaxCarSpeed) => 0.8,
+            (Surface::Carpet, Stat::CarGrip) => 1.5,
+            (Surface::Carpet, Stat::RollingResistanceCoefficient) => 1.5,
+            _ => 1.0,
+        }
+    }
+}
+
+// A patch of the track made of something other than the usual floor
+#[derive(Copy, Clone, Debug)]
+pub struct SurfaceZone {
+    pub bounds: BoundingBox,
+    pub surface: Surface,
+}
+
+// A pad that shoves you along its forward axis when you drive onto it
+#[derive(Copy, Clone, Debug)]
+pub struct BoostPad {
+    pub bounds: BoundingBox,
+    pub forward: DVec3,
+}
+
+impl PlayerEntity {
+    // We only feel a surface while our wheels are on it
+    pub fn update_surface(&mut self, ground: &GroundSample) {
+        self.surface = if self.is_aerial(ground) {
+            None
+        } else {
+            ground.surface
+        };
+    }
+
+    // Raises our top speed for a bit after a boost pad, starting `now`
+    pub fn boost_from_pad(&mut self, now: Instant) {
+        self.stats_changes.push(StatsChange {
+            stat: Stat::MaxCarSpeed,
+            multiplier: BOOST_PAD_TOP_SPEED_MULTIPLIER,
+            expiration_time: now + BOOST_PAD_DURATION,
+        });
+    }
+}
@@ -1,25 +1,32 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use chariot_core::player::choices::Chair;
+use chariot_core::player::choices::{Chair, Stat};
 use glam::DVec3;
 
 use crate::physics::bounding_box::BoundingBox;
-use crate::physics::ramp::RampCollisionResult;
+use crate::physics::drift::MIN_DRIFT_CHARGE;
+use crate::physics::ground::{sample_ground, GroundFace, GroundSample, RIDE_HEIGHT};
+use crate::physics::physics_changes::{PhysicsChange, PhysicsChangeType};
+use crate::physics::spatial_grid::{pick_mut, SpatialGrid};
+use crate::physics::surface::{Surface, BOOST_PAD_SPEED};
+use crate::physics::timestep::{FixedTimestep, TUNING_INTERVAL};
+use crate::physics::triangle::Triangle;
 use crate::progress::PlayerProgress;
 use chariot_core::entity_location::EntityLocation;
-use chariot_core::player::{
-    lap_info::LapInformation,
-    player_inputs::{EngineStatus, PlayerInputs, RotationStatus},
-};
+use chariot_core::player::player_inputs::{EngineStatus, PlayerInputs, RotationStatus};
 use chariot_core::GLOBAL_CONFIG;
 
 use crate::physics::player_entity::PlayerEntity;
 
+// one physics step at the rate everything was tuned at
+const STEP: f64 = TUNING_INTERVAL;
+
 fn get_starting_player_props() -> PlayerEntity {
     PlayerEntity {
         player_inputs: PlayerInputs {
             engine_status: EngineStatus::Accelerating(1.0),
             rotation_status: RotationStatus::NotInSpin,
+            drifting: false,
         },
 
         entity_location: EntityLocation {
@@ -30,6 +37,10 @@ fn get_starting_player_props() -> PlayerEntity {
 
         velocity: DVec3::new(2.0, 0.0, 1.0),
         angular_velocity: 0.0,
+        drift_charge: 0.0,
+        surface: None,
+        on_boost_pad: false,
+        stuck_time: 0.0,
 
         current_colliders: Vec::new(),
 
@@ -40,6 +51,7 @@ fn get_starting_player_props() -> PlayerEntity {
         sound_effects: Vec::new(),
         placement_data: PlayerProgress::PreGame,
         cached_place: None,
+        lap_timer: Default::default(),
         current_powerup: None,
         chair: Chair::Swivel,
         game_start_time: Instant::now(),
@@ -51,6 +63,7 @@ fn get_origin_cube() -> PlayerEntity {
         player_inputs: PlayerInputs {
             engine_status: EngineStatus::Neutral,
             rotation_status: RotationStatus::NotInSpin,
+            drifting: false,
         },
 
         entity_location: EntityLocation {
@@ -61,6 +74,10 @@ fn get_origin_cube() -> PlayerEntity {
 
         velocity: DVec3::ZERO,
         angular_velocity: 0.0,
+        drift_charge: 0.0,
+        surface: None,
+        on_boost_pad: false,
+        stuck_time: 0.0,
 
         current_colliders: Vec::new(),
 
@@ -71,6 +88,7 @@ fn get_origin_cube() -> PlayerEntity {
         sound_effects: Vec::new(),
         placement_data: PlayerProgress::PreGame,
         cached_place: None,
+        lap_timer: Default::default(),
         current_powerup: None,
         chair: Chair::Swivel,
         game_start_time: Instant::now(),
@@ -83,29 +101,29 @@ fn test_spinning() {
     props.velocity = DVec3::ZERO;
     props.player_inputs.rotation_status = RotationStatus::InSpinClockwise(1.0);
     props = props.do_physics_step(
-        1.0,
+        STEP,
         Vec::new(),
         Vec::new(),
         std::iter::empty(),
         &Vec::new(),
-        &RampCollisionResult::NoEffect,
+        &GroundSample::flat_floor(),
     );
 
-    assert_eq!(props.angular_velocity, GLOBAL_CONFIG.car_spin);
+    assert_eq!(props.angular_velocity, GLOBAL_CONFIG.car_spin * STEP);
 
     props.player_inputs.rotation_status = RotationStatus::NotInSpin;
     props = props.do_physics_step(
-        1.0,
+        STEP,
         Vec::new(),
         Vec::new(),
         std::iter::empty(),
         &Vec::new(),
-        &RampCollisionResult::NoEffect,
+        &GroundSample::flat_floor(),
     );
 
     assert_eq!(
         props.angular_velocity,
-        GLOBAL_CONFIG.car_spin * GLOBAL_CONFIG.rotation_reduction_coefficient
+        GLOBAL_CONFIG.car_spin * STEP * GLOBAL_CONFIG.rotation_reduction_coefficient
     );
 }
 
@@ -226,6 +244,249 @@ fn test_collision_on_30_deg_rotated_edges() {
     assert!(uwu_cube.bounding_box.is_colliding(&owo_cube.bounding_box));
 }
 
+#[test]
+fn test_oriented_collision_on_rotated_edges() {
+    // same two cubes as above, but now the corners really do poke into each
+    // other by 0.1 along the x-axis, so the shortest way out is backing off
+    // along one of the faces by 0.1 / sqrt(2)
+    let mut owo_cube = get_origin_cube();
+    let mut uwu_cube = get_origin_cube();
+
+    owo_cube.entity_location.unit_steer_direction =
+        DVec3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+    uwu_cube.entity_location.unit_steer_direction =
+        DVec3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+    uwu_cube.entity_location.position = DVec3::new(10.0 * 2.0_f64.sqrt() - 0.1, 0.0, 0.0);
+    uwu_cube.update_bounding_box();
+    owo_cube.update_bounding_box();
+
+    let contact = owo_cube.contact_with_player(&uwu_cube).unwrap();
+    assert!((contact.depth - 0.1 / 2.0_f64.sqrt()).abs() < 1e-6);
+    assert!((contact.normal.x + 2.0_f64.sqrt() / 2.0).abs() < 1e-6);
+    assert!((contact.normal.z.abs() - 2.0_f64.sqrt() / 2.0).abs() < 1e-6);
+    assert_eq!(contact.normal.y, 0.0);
+}
+
+#[test]
+fn test_oriented_noncollision_on_rotated_faces() {
+    // two cubes turned 45 degrees, lined up face to face along the diagonal
+    // and a little apart; their axis-aligned boxes overlap a bunch but the
+    // chairs themselves don't
+    let mut owo_cube = get_origin_cube();
+    let mut uwu_cube = get_origin_cube();
+
+    owo_cube.entity_location.unit_steer_direction =
+        DVec3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+    uwu_cube.entity_location.unit_steer_direction =
+        DVec3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+    uwu_cube.entity_location.position = DVec3::new(7.5, 0.0, 7.5);
+    uwu_cube.update_bounding_box();
+    owo_cube.update_bounding_box();
+
+    assert!(owo_cube.bounding_box.is_colliding(&uwu_cube.bounding_box));
+    assert!(owo_cube.contact_with_player(&uwu_cube).is_none());
+    assert_eq!(owo_cube.collision_with_player(&uwu_cube), None);
+}
+
+#[test]
+fn test_oriented_collision_on_30_deg_rotated_faces() {
+    // turned 30 degrees and slid along the direction it's facing, so the
+    // contact has to come out along that face rather than a world axis
+    let mut owo_cube = get_origin_cube();
+    let mut uwu_cube = get_origin_cube();
+
+    let facing = DVec3::new(3.0_f64.sqrt() / 2.0, 0.0, 1.0 / 2.0);
+    owo_cube.entity_location.unit_steer_direction = facing;
+    uwu_cube.entity_location.unit_steer_direction = facing;
+    uwu_cube.entity_location.position = facing * 9.0;
+    uwu_cube.update_bounding_box();
+    owo_cube.update_bounding_box();
+
+    let contact = owo_cube.contact_with_player(&uwu_cube).unwrap();
+    assert!((contact.depth - 1.0).abs() < 1e-6);
+    assert!((contact.normal + facing).length() < 1e-6);
+}
+
+#[test]
+fn test_player_collision_pushes_along_contact_normal() {
+    // clipping the corner of someone way off to the side: the old
+    // center-to-center push would've shoved us sideways too, but we're only
+    // actually touching along the x-axis
+    let mut owo_cube = get_origin_cube();
+    let mut uwu_cube = get_origin_cube();
+    owo_cube.velocity = DVec3::new(1.0, 0.0, 0.0);
+    uwu_cube.entity_location.position = DVec3::new(9.0, 0.0, 3.0);
+    uwu_cube.update_bounding_box();
+
+    let delta_v = owo_cube
+        .collision_with_player(&uwu_cube)
+        .unwrap()
+        .delta_velocity;
+    assert!(delta_v.x < 0.0);
+    assert_eq!(delta_v.z, 0.0);
+
+    // and once we're heading away there's nothing left to bounce off
+    owo_cube.velocity = DVec3::new(-1.0, 0.0, 0.0);
+    assert_eq!(
+        owo_cube
+            .collision_with_player(&uwu_cube)
+            .unwrap()
+            .delta_velocity,
+        DVec3::ZERO
+    );
+}
+
+// two unit cubes on the floor facing along x, the second a bit ahead of and
+// overlapping the first
+fn get_colliding_chairs(offset: DVec3) -> (PlayerEntity, PlayerEntity) {
+    let chair_at = |position: DVec3| {
+        let mut chair = get_origin_cube();
+        chair.size = DVec3::new(1.0, 1.0, 1.0);
+        chair.entity_location.position = position;
+        chair.update_bounding_box();
+        chair
+    };
+    (chair_at(DVec3::ZERO), chair_at(offset))
+}
+
+#[test]
+fn test_head_on_player_collision() {
+    let (mut owo, mut uwu) = get_colliding_chairs(DVec3::new(0.9, 0.0, 0.0));
+    owo.velocity = DVec3::new(10.0, 0.0, 0.0);
+    uwu.velocity = DVec3::new(-10.0, 0.0, 0.0);
+
+    let owo_hit = owo.collision_with_player(&uwu).unwrap();
+    let uwu_hit = uwu.collision_with_player(&owo).unwrap();
+
+    // same weight, so we swap speeds, less whatever the bounce loses
+    let restitution = GLOBAL_CONFIG.player_bounciness;
+    let expected = DVec3::new(-(1.0 + restitution) * 10.0, 0.0, 0.0);
+    assert!((owo_hit.delta_velocity - expected).length() < 1e-9);
+    assert!((owo_hit.delta_velocity + uwu_hit.delta_velocity).length() < 1e-9);
+
+    // straight through the middle, so nobody spins
+    assert!(owo_hit.delta_angular_velocity.abs() < 1e-9);
+    assert!(uwu_hit.delta_angular_velocity.abs() < 1e-9);
+
+    // and we each back off half the overlap
+    assert!((owo_hit.separation - DVec3::new(-0.05, 0.0, 0.0)).length() < 1e-9);
+    assert!((uwu_hit.separation - DVec3::new(0.05, 0.0, 0.0)).length() < 1e-9);
+}
+
+#[test]
+fn test_glancing_player_collision_spins_the_victim() {
+    // ramming into the side of someone sitting still, towards the back end
+    // of them and then towards the front
+    let side_hit = |along: f64| {
+        let (mut owo, uwu) = get_colliding_chairs(DVec3::new(along, 0.0, 0.95));
+        owo.velocity = DVec3::new(0.0, 0.0, 10.0);
+        (
+            owo.collision_with_player(&uwu).unwrap(),
+            uwu.collision_with_player(&owo).unwrap(),
+        )
+    };
+
+    let (owo_hit, uwu_hit) = side_hit(0.6);
+    // momentum is conserved...
+    assert!((owo_hit.delta_velocity + uwu_hit.delta_velocity).length() < 1e-9);
+    assert!(uwu_hit.delta_velocity.z > 0.0);
+    // ...but some of it went into spinning them, so they're not knocked
+    // away as hard as a head-on hit would
+    assert!(uwu_hit.delta_velocity.z < 10.0 * (1.0 + GLOBAL_CONFIG.player_bounciness) / 2.0);
+    assert!(uwu_hit.delta_angular_velocity.abs() > 0.1);
+
+    // hitting the other end of them spins them the other way
+    let (_, uwu_other_end_hit) = side_hit(-0.6);
+    assert!(
+        uwu_other_end_hit.delta_angular_velocity.signum()
+            != uwu_hit.delta_angular_velocity.signum()
+    );
+    assert!(
+        (uwu_other_end_hit.delta_angular_velocity + uwu_hit.delta_angular_velocity).abs() < 1e-9
+    );
+}
+
+#[test]
+fn test_heavy_chair_barely_notices_light_one() {
+    let (mut recliner, mut beanbag) = get_colliding_chairs(DVec3::new(0.9, 0.0, 0.0));
+    recliner.chair = Chair::Recliner;
+    beanbag.chair = Chair::Beanbag;
+    recliner.velocity = DVec3::new(5.0, 0.0, 0.0);
+
+    let recliner_hit = recliner.collision_with_player(&beanbag).unwrap();
+    let beanbag_hit = beanbag.collision_with_player(&recliner).unwrap();
+
+    let recliner_mass = Chair::Recliner.stat(&Stat::Mass);
+    let beanbag_mass = Chair::Beanbag.stat(&Stat::Mass);
+    assert!(recliner_mass > beanbag_mass);
+
+    // momentum is conserved, so the beanbag goes flying while the recliner
+    // hardly slows down
+    let momentum =
+        recliner_hit.delta_velocity * recliner_mass + beanbag_hit.delta_velocity * beanbag_mass;
+    assert!(momentum.length() < 1e-9);
+    assert!(beanbag_hit.delta_velocity.x > 5.0);
+    assert!(recliner_hit.delta_velocity.x.abs() < beanbag_hit.delta_velocity.x / 10.0);
+
+    // and the beanbag is the one that gets shoved out of the way
+    assert!(beanbag_hit.separation.length() > recliner_hit.separation.length() * 10.0);
+    assert!(
+        (beanbag_hit.separation.length() + recliner_hit.separation.length() - 0.1).abs() < 1e-9
+    );
+}
+
+#[test]
+fn test_bouncing_off_wall_uses_contact_normal() {
+    // turned 45 degrees and sliding into a wall on our right; only the part
+    // of our velocity going into the wall should flip
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(2.0, 2.0, 2.0);
+    props.entity_location.position = DVec3::new(0.0, 1.0, 0.0);
+    props.entity_location.unit_steer_direction =
+        DVec3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+    props.velocity = DVec3::new(1.0, 0.0, 1.0);
+    props.update_bounding_box();
+
+    let wall = BoundingBox::new(1.3, 5.0, 0.0, 5.0, -10.0, 10.0);
+    let bounced = props.do_physics_step(
+        STEP,
+        Vec::new(),
+        Triangle::from_box(&wall),
+        std::iter::empty(),
+        &Vec::new(),
+        &GroundSample::flat_floor(),
+    );
+
+    assert!(!bounced.current_colliders.is_empty());
+    assert!(bounced.velocity.x < 0.0);
+    assert!(bounced.velocity.z > 0.0);
+}
+
+#[test]
+fn test_no_bounce_off_wall_only_touching_our_envelope() {
+    // same chair, but the wall only reaches the corner of its axis-aligned
+    // box, which the chair itself doesn't fill
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(2.0, 2.0, 2.0);
+    props.entity_location.position = DVec3::new(0.0, 1.0, 0.0);
+    props.entity_location.unit_steer_direction =
+        DVec3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+    props.update_bounding_box();
+
+    let corner_wall = BoundingBox::new(1.0, 5.0, 0.0, 5.0, 1.0, 5.0);
+    assert!(props.bounding_box.is_colliding(&corner_wall));
+
+    let moved = props.do_physics_step(
+        STEP,
+        Vec::new(),
+        Triangle::from_box(&corner_wall),
+        std::iter::empty(),
+        &Vec::new(),
+        &GroundSample::flat_floor(),
+    );
+    assert!(moved.current_colliders.is_empty());
+}
+
 #[test]
 fn test_3d_bounding_box() {
     let mut cube = get_origin_cube();
@@ -242,3 +503,791 @@ fn test_3d_bounding_box() {
     assert!(actual_top * 0.999 < max_y && max_y < actual_top * 1.001);
     assert!(actual_bottom * 0.999 > min_y && min_y > actual_bottom * 1.001);
 }
+
+#[test]
+fn test_spatial_grid_finds_nearby_boxes() {
+    let boxes = vec![
+        BoundingBox::new(0.0, 1.0, 0.0, 1.0, 0.0, 1.0),
+        BoundingBox::new(50.0, 51.0, 0.0, 1.0, 50.0, 51.0),
+        // spans a bunch of cells, so it shouldn't come back more than once
+        BoundingBox::new(-20.0, 20.0, 0.0, 1.0, 2.0, 3.0),
+    ];
+    let grid = SpatialGrid::new(&boxes, 8.0);
+
+    let near_origin = BoundingBox::new(-1.0, 2.0, -1.0, 2.0, -1.0, 2.0);
+    assert_eq!(grid.query(&near_origin), vec![0, 2]);
+
+    let near_far_box = BoundingBox::new(49.0, 52.0, -1.0, 2.0, 49.0, 52.0);
+    assert_eq!(grid.query(&near_far_box), vec![1]);
+
+    let off_the_map = BoundingBox::new(500.0, 501.0, 0.0, 1.0, 500.0, 501.0);
+    assert!(grid.query(&off_the_map).is_empty());
+}
+
+#[test]
+fn test_spatial_grid_with_nothing_in_it() {
+    let grid = SpatialGrid::new(&[], 8.0);
+    assert!(grid
+        .query(&BoundingBox::new(-1.0, 1.0, -1.0, 1.0, -1.0, 1.0))
+        .is_empty());
+}
+
+#[test]
+fn test_pick_mut() {
+    let mut numbers = vec![10, 11, 12, 13, 14];
+    for number in pick_mut(&mut numbers, &[0, 2, 4]) {
+        *number = 0;
+    }
+    assert_eq!(numbers, vec![0, 11, 0, 13, 0]);
+}
+
+#[test]
+fn test_triangle_contact_pushes_box_out_the_near_side() {
+    let wall = Triangle::new(
+        DVec3::new(0.0, 0.0, -5.0),
+        DVec3::new(0.0, 0.0, 5.0),
+        DVec3::new(0.0, 5.0, 0.0),
+    )
+    .unwrap();
+
+    let mut cube = get_origin_cube();
+    cube.size = DVec3::new(2.0, 2.0, 2.0);
+    cube.entity_location.position = DVec3::new(-0.75, 1.0, 0.0);
+    let contact = wall.contact_with_box(&cube.oriented_box()).unwrap();
+    assert!((contact.depth - 0.25).abs() < 1e-6);
+    assert!((contact.normal - DVec3::new(-1.0, 0.0, 0.0)).length() < 1e-6);
+
+    cube.entity_location.position = DVec3::new(0.75, 1.0, 0.0);
+    let contact = wall.contact_with_box(&cube.oriented_box()).unwrap();
+    assert!((contact.normal - DVec3::new(1.0, 0.0, 0.0)).length() < 1e-6);
+
+    cube.entity_location.position = DVec3::new(1.5, 1.0, 0.0);
+    assert!(wall.contact_with_box(&cube.oriented_box()).is_none());
+}
+
+#[test]
+fn test_degenerate_triangles_are_skipped() {
+    assert!(Triangle::new(DVec3::ZERO, DVec3::X, DVec3::X * 2.0).is_none());
+    assert_eq!(
+        Triangle::from_box(&BoundingBox::new(0.0, 1.0, 0.0, 1.0, 0.0, 1.0)).len(),
+        12
+    );
+}
+
+// a wall running diagonally across the ground, at 45 degrees to both axes
+fn get_diagonal_wall() -> Vec<Triangle> {
+    let a = DVec3::new(2.0, 0.0, -10.0);
+    let b = DVec3::new(-18.0, 0.0, 10.0);
+    let up = DVec3::new(0.0, 5.0, 0.0);
+    vec![
+        Triangle::new(a, b, b + up).unwrap(),
+        Triangle::new(a, b + up, a + up).unwrap(),
+    ]
+}
+
+#[test]
+fn test_bouncing_off_angled_wall() {
+    // driving straight along x into a wall that's turned 45 degrees should
+    // send us off along z, not straight back the way we came
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(2.0, 2.0, 2.0);
+    props.entity_location.position = DVec3::new(-8.5, 1.0, 0.0);
+    props.velocity = DVec3::new(0.5, 0.0, 0.0);
+    props.update_bounding_box();
+
+    let bounced = props.do_physics_step(
+        STEP,
+        Vec::new(),
+        get_diagonal_wall(),
+        std::iter::empty(),
+        &Vec::new(),
+        &GroundSample::flat_floor(),
+    );
+
+    assert!(!bounced.current_colliders.is_empty());
+    assert!(bounced.velocity.z < 0.0);
+    assert!(bounced.velocity.x.abs() < bounced.velocity.z.abs() * 0.1);
+}
+
+#[test]
+fn test_sliding_along_wall_pushes_us_back_out() {
+    // already a bit inside a wall and driving alongside it: we keep going
+    // along the wall but get moved back out of it
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(2.0, 2.0, 2.0);
+    props.entity_location.position = DVec3::new(-0.8, 1.0, 0.0);
+    props.velocity = DVec3::new(0.0, 0.0, 0.3);
+    props.update_bounding_box();
+
+    let wall = Triangle::from_box(&BoundingBox::new(0.0, 5.0, 0.0, 5.0, -20.0, 20.0));
+    let moved = props.do_physics_step(
+        STEP,
+        Vec::new(),
+        wall,
+        std::iter::empty(),
+        &Vec::new(),
+        &GroundSample::flat_floor(),
+    );
+
+    assert!((moved.entity_location.position.x + 1.0).abs() < 1e-6);
+    assert!(moved.velocity.z > 0.0);
+    assert_eq!(moved.velocity.x, 0.0);
+}
+
+// a square of ground from -10 to 10 on x and z, tilted so it climbs one unit
+// for every unit along x, split into two triangles
+fn get_hill(base_height: f64, boosts_uphill: bool) -> Vec<GroundFace> {
+    let corner = |x: f64, z: f64| DVec3::new(x, base_height + x, z);
+    [
+        Triangle::new(
+            corner(-10.0, -10.0),
+            corner(10.0, -10.0),
+            corner(10.0, 10.0),
+        ),
+        Triangle::new(
+            corner(-10.0, -10.0),
+            corner(10.0, 10.0),
+            corner(-10.0, 10.0),
+        ),
+    ]
+    .into_iter()
+    .map(|triangle| GroundFace {
+        triangle: triangle.unwrap(),
+        boosts_uphill,
+    })
+    .collect()
+}
+
+#[test]
+fn test_sampling_ground_height_and_normal() {
+    let hill = get_hill(0.0, false);
+
+    let sample = sample_ground(&hill, 3.0, -2.0, 100.0);
+    assert!((sample.height - 3.0).abs() < 1e-9);
+    let expected_normal = DVec3::new(-1.0, 1.0, 0.0).normalize();
+    assert!((sample.normal - expected_normal).length() < 1e-9);
+    assert!((sample.uphill().unwrap() - DVec3::new(1.0, 1.0, 0.0).normalize()).length() < 1e-9);
+
+    // off the edge of the hill is just the floor
+    assert_eq!(
+        sample_ground(&hill, 30.0, 0.0, 100.0),
+        GroundSample::flat_floor()
+    );
+    assert_eq!(GroundSample::flat_floor().uphill(), None);
+}
+
+#[test]
+fn test_sampling_ground_picks_the_level_we_are_on() {
+    // a floor at height 0 and another right above it at height 5
+    let level = |height: f64| -> Vec<GroundFace> {
+        let corner = |x: f64, z: f64| DVec3::new(x, height, z);
+        [
+            Triangle::new(
+                corner(-10.0, -10.0),
+                corner(10.0, -10.0),
+                corner(10.0, 10.0),
+            ),
+            Triangle::new(
+                corner(-10.0, -10.0),
+                corner(10.0, 10.0),
+                corner(-10.0, 10.0),
+            ),
+        ]
+        .into_iter()
+        .map(|triangle| GroundFace {
+            triangle: triangle.unwrap(),
+            boosts_uphill: false,
+        })
+        .collect()
+    };
+    let mut two_levels = level(0.0);
+    two_levels.extend(level(5.0));
+
+    assert_eq!(sample_ground(&two_levels, 0.0, 0.0, 0.32).height, 0.0);
+    assert_eq!(sample_ground(&two_levels, 0.0, 0.0, 5.32).height, 5.0);
+}
+
+#[test]
+fn test_tilting_to_match_the_ground() {
+    let hill = get_hill(0.0, false);
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(1.0, 2.0, 1.0);
+    props.entity_location.position = DVec3::new(2.0, 2.0 + RIDE_HEIGHT, 0.0);
+
+    let ground = props.update_upwards_from_ground(&hill, STEP);
+    assert!((ground.height - 2.0).abs() < 1e-9);
+    assert!((props.entity_location.unit_upward_direction - ground.normal).length() < 1e-9);
+
+    // way up in the air we start leveling back out instead
+    props.entity_location.position.y = 8.0;
+    props.update_upwards_from_ground(&hill, STEP);
+    assert!(props.entity_location.unit_upward_direction.y > ground.normal.y);
+}
+
+#[test]
+fn test_driving_up_a_ramp() {
+    let ramp = get_hill(0.0, true);
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(1.0, 2.0, 1.0);
+    props.entity_location.position = DVec3::new(2.0, 2.0 + RIDE_HEIGHT, 0.0);
+    props.velocity = DVec3::new(0.2, 0.0, 0.0);
+
+    let ground = props.update_upwards_from_ground(&ramp, STEP);
+    let moved = props.do_physics_step(
+        STEP,
+        Vec::new(),
+        Vec::new(),
+        std::iter::empty(),
+        &vec![BoundingBox::new(-10.0, 10.0, -1.0, 20.0, -10.0, 10.0)],
+        &ground,
+    );
+
+    // we get shoved up the slope, and never sink into it
+    assert!(moved.velocity.x > 0.2);
+    assert!(moved.velocity.y > 0.0);
+    assert!(moved.entity_location.position.y >= ground.height + RIDE_HEIGHT);
+}
+
+#[test]
+fn test_rolling_off_a_ledge_instead_of_snagging_on_it() {
+    // driving off the side of a raised platform: the platform's side is a
+    // wall, but it's below us so we shouldn't bounce off of it
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(1.0, 2.0, 1.0);
+    props.entity_location.position = DVec3::new(9.8, 1.0 + RIDE_HEIGHT, 0.0);
+    props.velocity = DVec3::new(0.2, 0.0, 0.0);
+    props.update_bounding_box();
+
+    let platform_side = Triangle::from_box(&BoundingBox::new(-10.0, 10.0, 0.0, 1.0, -10.0, 10.0));
+    let moved = props.do_physics_step(
+        STEP,
+        Vec::new(),
+        platform_side,
+        std::iter::empty(),
+        &Vec::new(),
+        &GroundSample {
+            height: 1.0,
+            normal: DVec3::Y,
+            boosts_uphill: false,
+            surface: None,
+            boost_pad: None,
+        },
+    );
+
+    assert!(moved.current_colliders.is_empty());
+    assert!(moved.velocity.x > 0.0);
+}
+
+#[test]
+fn test_fixed_timestep_carries_over_leftover_time() {
+    let mut clock = FixedTimestep::new(Duration::from_millis(30), 4);
+    let start = Instant::now();
+    assert!((clock.step() - 0.0075).abs() < 1e-12);
+
+    // the first tick only starts the clock
+    assert_eq!(clock.steps_due(start), 0);
+    assert_eq!(clock.steps_due(start + Duration::from_millis(30)), 4);
+    // 10ms is one step with 2.5ms left over, which 5ms more tops up
+    assert_eq!(clock.steps_due(start + Duration::from_millis(40)), 1);
+    assert_eq!(clock.steps_due(start + Duration::from_millis(45)), 1);
+
+    // a long stall doesn't make us try to simulate all of it at once
+    assert_eq!(clock.steps_due(start + Duration::from_secs(5)), 12);
+    assert_eq!(clock.steps_due(start + Duration::from_secs(5)), 0);
+}
+
+// drives forwards while turning for half of `duration`, then lets go of the
+// turn for the rest of it, in steps of `time_step`
+fn drive_for(duration: f64, time_step: f64) -> PlayerEntity {
+    let mut props = get_starting_player_props();
+    props.size = DVec3::new(1.0, 1.0, 1.0);
+    props.entity_location.position = DVec3::new(0.0, RIDE_HEIGHT, 0.0);
+    props.velocity = DVec3::ZERO;
+    props.player_inputs.rotation_status = RotationStatus::InSpinClockwise(1.0);
+
+    let steps = (duration / time_step).round() as usize;
+    for step in 0..steps {
+        if step == steps / 2 {
+            props.player_inputs.rotation_status = RotationStatus::NotInSpin;
+        }
+        props.update_bounding_box();
+        props = props.do_physics_step(
+            time_step,
+            Vec::new(),
+            Vec::new(),
+            std::iter::empty(),
+            &vec![BoundingBox::new(-100.0, 100.0, -1.0, 5.0, -100.0, 100.0)],
+            &GroundSample::flat_floor(),
+        );
+    }
+    props
+}
+
+#[test]
+fn test_physics_is_the_same_at_any_step_rate() {
+    let coarse = drive_for(0.48, STEP);
+    let fine = drive_for(0.48, STEP / 8.0);
+
+    // we got somewhere, and ended up in (nearly) the same place going
+    // (nearly) the same way regardless of how finely we chopped up the time
+    let distance = coarse.entity_location.position.length();
+    assert!(distance > 1.0);
+    assert!(
+        (coarse.entity_location.position - fine.entity_location.position).length()
+            < distance * 0.05
+    );
+    assert!((coarse.velocity - fine.velocity).length() < coarse.velocity.length() * 0.05);
+    assert!(
+        (coarse.entity_location.unit_steer_direction - fine.entity_location.unit_steer_direction)
+            .length()
+            < 0.05
+    );
+    assert!((coarse.angular_velocity - fine.angular_velocity).abs() < 0.05);
+}
+
+#[test]
+fn test_fast_chair_does_not_tunnel_through_thin_wall() {
+    // a paper-thin wall a few units ahead of a chair going fast enough to
+    // end up well past it after one step
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(1.0, 2.0, 1.0);
+    props.entity_location.position = DVec3::new(0.0, 1.0, 0.0);
+    props.velocity = DVec3::new(200.0, 0.0, 0.0);
+    props.update_bounding_box();
+
+    let wall_at = |x: f64| {
+        let bottom = DVec3::new(x, 0.0, -10.0);
+        let top = DVec3::new(x, 5.0, 10.0);
+        vec![
+            Triangle::new(bottom, DVec3::new(x, 0.0, 10.0), top).unwrap(),
+            Triangle::new(bottom, top, DVec3::new(x, 5.0, -10.0)).unwrap(),
+        ]
+    };
+
+    let stopped = props.do_physics_step(
+        STEP,
+        Vec::new(),
+        wall_at(3.0),
+        std::iter::empty(),
+        &Vec::new(),
+        &GroundSample::flat_floor(),
+    );
+    // we stop right up against the wall...
+    let front = stopped.entity_location.position.x + 0.5;
+    assert!(front <= 3.0 + 1e-3);
+    assert!(front > 3.0 - 0.01);
+
+    // ...and bounce off of it next step rather than pressing on through
+    let mut stopped = stopped;
+    stopped.update_bounding_box();
+    let bounced = stopped.do_physics_step(
+        STEP,
+        Vec::new(),
+        wall_at(3.0),
+        std::iter::empty(),
+        &Vec::new(),
+        &GroundSample::flat_floor(),
+    );
+    assert!(bounced.velocity.x < 0.0);
+    assert!(bounced.entity_location.position.x + 0.5 <= 3.0 + 1e-3);
+}
+
+// sliding along x at a good clip while facing 45 degrees off of it
+fn get_sliding_chair(drifting: bool) -> PlayerEntity {
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(1.0, 1.0, 1.0);
+    props.entity_location.position = DVec3::new(0.0, RIDE_HEIGHT, 0.0);
+    props.entity_location.unit_steer_direction =
+        DVec3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+    props.velocity = DVec3::new(10.0, 0.0, 0.0);
+    props.player_inputs.drifting = drifting;
+    props.update_bounding_box();
+    props
+}
+
+fn slide_for(mut props: PlayerEntity, steps: usize) -> PlayerEntity {
+    for _ in 0..steps {
+        props = props.do_physics_step(
+            STEP,
+            Vec::new(),
+            Vec::new(),
+            std::iter::empty(),
+            &vec![BoundingBox::new(-100.0, 100.0, -1.0, 5.0, -100.0, 100.0)],
+            &GroundSample::flat_floor(),
+        );
+    }
+    props
+}
+
+#[test]
+fn test_drifting_keeps_more_sideways_speed() {
+    let gripping = slide_for(get_sliding_chair(false), 10);
+    let drifting = slide_for(get_sliding_chair(true), 10);
+
+    let gripping_sideways = gripping.sideways_velocity(gripping.velocity).length();
+    let drifting_sideways = drifting.sideways_velocity(drifting.velocity).length();
+    assert!(drifting_sideways > gripping_sideways);
+
+    // and only drifting fills up the meter
+    assert_eq!(gripping.drift_charge, 0.0);
+    assert!(drifting.drift_charge > 0.0);
+}
+
+#[test]
+fn test_drift_charge_depends_on_angle() {
+    let sideways = get_sliding_chair(true);
+    let mut straight = get_sliding_chair(true);
+    straight.entity_location.unit_steer_direction = DVec3::X;
+
+    let sideways_charge = sideways.drift_charge_after(sideways.velocity, 0.5);
+    assert!(sideways_charge > 0.0);
+    assert!(straight.drift_charge_after(straight.velocity, 0.5) < 1e-9);
+
+    // drifting for longer fills it up more, but never past full
+    assert!(sideways.drift_charge_after(sideways.velocity, 1.0) > sideways_charge);
+    assert_eq!(sideways.drift_charge_after(sideways.velocity, 100.0), 1.0);
+
+    // too slow to count as a drift at all
+    assert_eq!(
+        sideways.drift_charge_after(DVec3::new(0.5, 0.0, 0.0), 0.5),
+        0.0
+    );
+}
+
+#[test]
+fn test_releasing_drift_boosts_us() {
+    let now = Instant::now();
+
+    let mut barely = get_sliding_chair(true);
+    barely.drift_charge = MIN_DRIFT_CHARGE / 2.0;
+    barely.release_drift(now);
+    assert!(barely.stats_changes.is_empty());
+    assert_eq!(barely.drift_charge, 0.0);
+
+    let release_with = |charge: f64| {
+        let mut props = get_sliding_chair(true);
+        props.drift_charge = charge;
+        props.release_drift(now);
+        assert_eq!(props.drift_charge, 0.0);
+        props
+    };
+    let half = release_with(0.5);
+    let full = release_with(1.0);
+
+    let top_speed = Chair::Swivel.stat(&Stat::MaxCarSpeed);
+    assert!(full.stat(Stat::MaxCarSpeed) > half.stat(Stat::MaxCarSpeed));
+    assert!(half.stat(Stat::MaxCarSpeed) > top_speed);
+    assert!(full.stat(Stat::CarAccelerator) > half.stat(Stat::CarAccelerator));
+
+    // a fuller meter lasts longer too
+    let lasts_until = |props: &PlayerEntity| {
+        props
+            .stats_changes
+            .iter()
+            .map(|change| change.expiration_time)
+            .max()
+            .unwrap()
+    };
+    assert!(lasts_until(&full) > lasts_until(&half));
+}
+
+fn get_ground_made_of(surface: Option<Surface>, boost_pad: Option<DVec3>) -> GroundSample {
+    GroundSample {
+        surface,
+        boost_pad,
+        ..GroundSample::flat_floor()
+    }
+}
+
+fn step_on(mut props: PlayerEntity, ground: &GroundSample, steps: usize) -> PlayerEntity {
+    for _ in 0..steps {
+        props.update_bounding_box();
+        props.update_surface(ground);
+        props = props.do_physics_step(
+            STEP,
+            Vec::new(),
+            Vec::new(),
+            std::iter::empty(),
+            &vec![BoundingBox::new(-100.0, 100.0, -1.0, 5.0, -100.0, 100.0)],
+            ground,
+        );
+    }
+    props
+}
+
+#[test]
+fn test_mud_slows_us_down() {
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(1.0, 1.0, 1.0);
+    props.entity_location.position = DVec3::new(0.0, RIDE_HEIGHT, 0.0);
+    props.entity_location.unit_steer_direction = DVec3::Z;
+    props.player_inputs.engine_status = EngineStatus::Accelerating(1.0);
+    props.velocity = DVec3::Z * Chair::Swivel.stat(&Stat::MaxCarSpeed);
+
+    let floor = step_on(props, &get_ground_made_of(None, None), 1);
+    let mud = step_on(floor, &get_ground_made_of(Some(Surface::Mud), None), 1);
+
+    assert_eq!(mud.surface, Some(Surface::Mud));
+    let mud_top_speed = Chair::Swivel.stat(&Stat::MaxCarSpeed) * 0.5;
+    assert!((mud.stat(Stat::MaxCarSpeed) - mud_top_speed).abs() < 1e-9);
+    assert!(mud.velocity.length() <= mud_top_speed + 1e-9);
+
+    // and we're back to normal once we're out of it
+    let out = step_on(mud, &get_ground_made_of(None, None), 1);
+    assert_eq!(out.surface, None);
+    assert_eq!(
+        out.stat(Stat::MaxCarSpeed),
+        Chair::Swivel.stat(&Stat::MaxCarSpeed)
+    );
+}
+
+#[test]
+fn test_ice_is_slippier_than_carpet() {
+    let ice = step_on(
+        get_sliding_chair(false),
+        &get_ground_made_of(Some(Surface::Ice), None),
+        10,
+    );
+    let carpet = step_on(
+        get_sliding_chair(false),
+        &get_ground_made_of(Some(Surface::Carpet), None),
+        10,
+    );
+
+    assert!(
+        ice.sideways_velocity(ice.velocity).length()
+            > carpet.sideways_velocity(carpet.velocity).length()
+    );
+}
+
+#[test]
+fn test_no_surface_in_the_air() {
+    let mut props = get_sliding_chair(false);
+    props.entity_location.position.y = 5.0;
+    props.update_surface(&get_ground_made_of(Some(Surface::Ice), None));
+    assert_eq!(props.surface, None);
+}
+
+#[test]
+fn test_boost_pad_kicks_us_once() {
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(1.0, 1.0, 1.0);
+    props.entity_location.position = DVec3::new(0.0, RIDE_HEIGHT, 0.0);
+    props.entity_location.unit_steer_direction = DVec3::X;
+    props.velocity = DVec3::X * 5.0;
+    let pad = get_ground_made_of(None, Some(DVec3::X));
+
+    let boosted = step_on(props, &pad, 1);
+    assert!(boosted.on_boost_pad);
+    assert!(boosted.velocity.x > 5.0 + BOOST_PAD_SPEED * 0.9);
+    assert!(boosted.stat(Stat::MaxCarSpeed) > Chair::Swivel.stat(&Stat::MaxCarSpeed));
+
+    // still on the same pad, so no second kick
+    let boosted_speed = boosted.velocity.x;
+    let still_on = step_on(boosted, &pad, 1);
+    assert!(still_on.velocity.x <= boosted_speed);
+    assert_eq!(still_on.stats_changes.len(), 1);
+}
+
+#[test]
+fn test_ghosts_drive_through_other_players() {
+    let (mut owo, mut uwu) = get_colliding_chairs(DVec3::new(0.9, 0.0, 0.0));
+    owo.velocity = DVec3::new(10.0, 0.0, 0.0);
+    uwu.velocity = DVec3::new(-10.0, 0.0, 0.0);
+    uwu.respawn_at(uwu.entity_location, Instant::now());
+    assert!(uwu.is_ghost());
+
+    let step_into = |props: &PlayerEntity, other: &PlayerEntity| {
+        props.do_physics_step(
+            STEP,
+            vec![other],
+            Vec::new(),
+            std::iter::empty(),
+            &vec![BoundingBox::new(-100.0, 100.0, -1.0, 5.0, -100.0, 100.0)],
+            &GroundSample::flat_floor(),
+        )
+    };
+
+    // neither of us bounces off the other, ghost or not
+    assert!(step_into(&owo, &uwu).velocity.x > 0.0);
+    assert!(step_into(&uwu, &owo).velocity.length() < 1e-9);
+}
+
+#[test]
+fn test_respawning_stops_us_dead() {
+    let mut props = get_sliding_chair(true);
+    props.angular_velocity = 2.0;
+    props.drift_charge = 0.5;
+    props.stuck_time = 10.0;
+
+    let checkpoint = EntityLocation {
+        position: DVec3::new(5.0, 1.0, 5.0),
+        unit_steer_direction: DVec3::Z,
+        unit_upward_direction: DVec3::Y,
+    };
+    props.respawn_at(checkpoint, Instant::now());
+
+    assert_eq!(props.entity_location.position, checkpoint.position);
+    assert_eq!(props.velocity, DVec3::ZERO);
+    assert_eq!(props.angular_velocity, 0.0);
+    assert_eq!(props.drift_charge, 0.0);
+    assert!(!props.is_stuck());
+    assert!(props.is_ghost());
+}
+
+#[test]
+fn test_getting_wedged_between_walls_counts_as_stuck() {
+    // a unit cube poking a little way into a wall on either side
+    let mut props = get_origin_cube();
+    props.size = DVec3::new(1.0, 1.0, 1.0);
+    props.entity_location.position = DVec3::new(0.0, RIDE_HEIGHT, 0.0);
+    props.update_bounding_box();
+    let walls: Vec<Triangle> = [
+        BoundingBox::new(0.4, 2.0, -1.0, 5.0, -5.0, 5.0),
+        BoundingBox::new(-2.0, -0.4, -1.0, 5.0, -5.0, 5.0),
+    ]
+    .iter()
+    .flat_map(Triangle::from_box)
+    .collect();
+
+    // (plus a few steps for us to settle in between them)
+    let stuck_for = GLOBAL_CONFIG.respawn_stuck_ms as f64 / 1000.0;
+    for _ in 0..(stuck_for / STEP).ceil() as usize + 5 {
+        props.update_bounding_box();
+        props = props.do_physics_step(
+            STEP,
+            Vec::new(),
+            walls.clone(),
+            std::iter::empty(),
+            &vec![BoundingBox::new(-100.0, 100.0, -1.0, 5.0, -100.0, 100.0)],
+            &GroundSample::flat_floor(),
+        );
+    }
+    assert!(props.is_stuck());
+
+    // driving off freely starts the clock over
+    let moving = props.stuck_time_after(DVec3::X, false, STEP);
+    assert_eq!(moving, 0.0);
+}
+
+#[test]
+fn test_one_off_kicks_happen_once_per_tick() {
+    let mut player = get_starting_player_props();
+    player.velocity = DVec3::X;
+    player.player_inputs.rotation_status = RotationStatus::NotInSpin;
+    for change_type in [
+        PhysicsChangeType::IAmSpeed,
+        PhysicsChangeType::InSpainButTheAIsSilent,
+    ] {
+        player.physics_changes.push(PhysicsChange {
+            change_type,
+            expiration_time: Instant::now() + Duration::from_secs(1),
+        });
+    }
+
+    // however many substeps a tick gets split into, they don't kick us again
+    for _ in 0..4 {
+        player.apply_physics_changes(STEP / 4.0);
+    }
+    assert_eq!(player.velocity, DVec3::X);
+    assert_eq!(player.angular_velocity, 0.0);
+
+    player.change_inputs_per_physics_changes();
+    assert_eq!(player.velocity, DVec3::X * (TUNING_INTERVAL + 30.0));
+    assert_eq!(
+        player.angular_velocity,
+        player.stat(Stat::CarSpin) * TUNING_INTERVAL
+    );
+}
+
+// a big square of pillars spaced evenly apart, so adding more of them makes
+// the track bigger rather than more cluttered around any one player
+fn get_synthetic_track(collider_count: usize) -> Vec<Triangle> {
+    let per_side = (collider_count as f64).sqrt().ceil() as usize;
+    (0..collider_count)
+        .flat_map(|i| {
+            let x = (i % per_side) as f64 * 6.0;
+            let z = (i / per_side) as f64 * 6.0;
+            Triangle::from_box(&BoundingBox::new(x, x + 1.0, 0.0, 3.0, z, z + 1.0))
+        })
+        .collect()
+}
+
+// not really a test, but there's nowhere better to put it; run with
+// `cargo test --release -- --ignored --nocapture bench_` to see the numbers
+#[test]
+#[ignore]
+fn bench_physics_tick_with_many_colliders() {
+    const TICKS: u32 = 200;
+    let mut per_tick_times = vec![];
+
+    for collider_count in [100, 1_000, 10_000, 100_000] {
+        let colliders = get_synthetic_track(collider_count);
+        let grid = SpatialGrid::new(
+            &colliders
+                .iter()
+                .map(Triangle::bounding_box)
+                .collect::<Vec<_>>(),
+            8.0,
+        );
+
+        let middle = (collider_count as f64).sqrt() * 3.0;
+        let players: Vec<PlayerEntity> = (0..4)
+            .map(|n| {
+                let mut player = get_starting_player_props();
+                player.size = DVec3::new(1.0, 1.0, 1.0);
+                player.entity_location.position = DVec3::new(middle + n as f64 * 2.0, 0.5, middle);
+                player.update_bounding_box();
+                player
+            })
+            .collect();
+
+        let start = Instant::now();
+        for _ in 0..TICKS {
+            for player in &players {
+                let nearby = grid
+                    .query(&player.bounding_box)
+                    .into_iter()
+                    .map(|index| colliders[index])
+                    .collect();
+                player.do_physics_step(
+                    STEP,
+                    Vec::new(),
+                    nearby,
+                    std::iter::empty(),
+                    &Vec::new(),
+                    &GroundSample::flat_floor(),
+                );
+            }
+        }
+        let grid_tick = start.elapsed() / TICKS;
+
+        // what we used to do: hand every collider on the track to every player
+        let start = Instant::now();
+        for _ in 0..TICKS {
+            for player in &players {
+                player.do_physics_step(
+                    STEP,
+                    Vec::new(),
+                    colliders.clone(),
+                    std::iter::empty(),
+                    &Vec::new(),
+                    &GroundSample::flat_floor(),
+                );
+            }
+        }
+        let full_scan_tick = start.elapsed() / TICKS;
+
+        println!(
+            "{:>7} colliders: {:>10?} per tick with the grid, {:>10?} without",
+            collider_count, grid_tick, full_scan_tick
+        );
+        per_tick_times.push(grid_tick);
+    }
+
+    // generous, since timing on a busy machine is noisy; a linear scan would
+    // be roughly 1000x slower at the top end
+    let smallest = per_tick_times.first().unwrap().as_secs_f64();
+    let largest = per_tick_times.last().unwrap().as_secs_f64();
+    assert!(largest < smallest * 20.0);
+}
@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+// Some of the tuning (how much spin is left after a step, how much speed you
+// lose off-track) was done back when physics ran once per 30ms server tick,
+// so those coefficients are still given per this many seconds
+pub const TUNING_INTERVAL: f64 = 0.03;
+
+// never try to catch up on more than this many ticks' worth of physics at
+// once, or one slow tick makes the next one slower and so on
+const MAX_TICKS_TO_CATCH_UP: u32 = 3;
+
+// Hands out fixed-length physics steps for however much real time has
+// passed, carrying whatever's left over into the next tick, so the physics
+// comes out the same no matter how evenly the server manages to tick
+pub struct FixedTimestep {
+    step: f64,
+    max_steps: u32,
+    accumulated: f64,
+    last_update: Option<Instant>,
+}
+
+impl FixedTimestep {
+    pub fn new(tick_length: Duration, substeps: u32) -> FixedTimestep {
+        let substeps = substeps.max(1);
+        FixedTimestep {
+            step: tick_length.as_secs_f64() / substeps as f64,
+            max_steps: substeps * MAX_TICKS_TO_CATCH_UP,
+            accumulated: 0.0,
+            last_update: None,
+        }
+    }
+
+    // how long each step is, in seconds
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    // How many steps to run to catch up with `now`; the first call only
+    // starts the clock
+    pub fn steps_due(&mut self, now: Instant) -> u32 {
+        if let Some(last_update) = self.last_update {
+            self.accumulated += now.saturating_duration_since(last_update).as_secs_f64();
+        }
+        self.last_update = Some(now);
+
+        let max_time = self.step * self.max_steps as f64;
+        if self.accumulated > max_time {
+            self.accumulated = max_time;
+        }
+
+        // a hair of slack so float error doesn't cost us a whole step
+        let steps = ((self.accumulated + 1e-9) / self.step).floor() as u32;
+        self.accumulated = (self.accumulated - steps as f64 * self.step).max(0.0);
+        steps
+    }
+}
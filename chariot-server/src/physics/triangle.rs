@@ -0,0 +1,136 @@
+use glam::DVec3;
+
+use crate::physics::bounding_box::BoundingBox;
+use crate::physics::oriented_box::{Contact, OrientedBox};
+
+// same idea as in oriented_box: anything shorter than this is a degenerate
+// axis we can't learn anything from
+const DEGENERATE_EPSILON: f64 = 1e-9;
+
+// One face of a wall, straight out of the track's collision meshes
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Triangle {
+    pub vertices: [DVec3; 3],
+}
+
+impl Triangle {
+    // None for triangles with no area, which can't be touched anyways
+    pub fn new(a: DVec3, b: DVec3, c: DVec3) -> Option<Triangle> {
+        if (b - a).cross(c - a).length_squared() < DEGENERATE_EPSILON {
+            return None;
+        }
+
+        Some(Triangle {
+            vertices: [a, b, c],
+        })
+    }
+
+    // the twelve triangles making up the outside of a box, for anything we
+    // only have bounds for
+    pub fn from_box(bounds: &BoundingBox) -> Vec<Triangle> {
+        // corners numbered by which of x, y and z (bits 0, 1 and 2) are at
+        // their max
+        let corner = |index: usize| {
+            DVec3::new(
+                if index & 1 != 0 {
+                    bounds.max_x
+                } else {
+                    bounds.min_x
+                },
+                if index & 2 != 0 {
+                    bounds.max_y
+                } else {
+                    bounds.min_y
+                },
+                if index & 4 != 0 {
+                    bounds.max_z
+                } else {
+                    bounds.min_z
+                },
+            )
+        };
+
+        // each face as four corners going around its edge
+        let faces = [
+            [0, 2, 6, 4],
+            [1, 3, 7, 5],
+            [0, 1, 5, 4],
+            [2, 3, 7, 6],
+            [0, 1, 3, 2],
+            [4, 5, 7, 6],
+        ];
+
+        faces
+            .iter()
+            .flat_map(|face| {
+                let [a, b, c, d] = face.map(corner);
+                [Triangle::new(a, b, c), Triangle::new(a, c, d)]
+            })
+            .flatten()
+            .collect()
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        let [a, b, c] = self.vertices;
+        BoundingBox::from_vecs(a.min(b).min(c), a.max(b).max(c))
+    }
+
+    pub fn normal(&self) -> DVec3 {
+        let [a, b, c] = self.vertices;
+        (b - a).cross(c - a).normalize()
+    }
+
+    // Separating axis test between this triangle and a box: the candidates
+    // are the triangle's normal, the box's face normals, and the crosses of
+    // the triangle's edges with the box's axes. The returned normal is the
+    // way to push the box to get it off of the triangle
+    pub fn contact_with_box(&self, other: &OrientedBox) -> Option<Contact> {
+        let [a, b, c] = self.vertices;
+
+        let mut candidate_axes: Vec<DVec3> = Vec::with_capacity(13);
+        candidate_axes.push(self.normal());
+        candidate_axes.extend_from_slice(&other.axes);
+        for edge in [b - a, c - b, a - c] {
+            for axis in other.axes {
+                let cross = edge.cross(axis);
+                if cross.length_squared() > DEGENERATE_EPSILON {
+                    candidate_axes.push(cross.normalize());
+                }
+            }
+        }
+
+        let mut best: Option<Contact> = None;
+        for axis in candidate_axes {
+            let projections = self.vertices.map(|vertex| vertex.dot(axis));
+            let triangle_min = projections[0].min(projections[1]).min(projections[2]);
+            let triangle_max = projections[0].max(projections[1]).max(projections[2]);
+
+            let center = other.center.dot(axis);
+            let radius = other.projected_radius(axis);
+
+            // how far the box would have to go either way along the axis to
+            // stop overlapping
+            let push_forward = triangle_max - (center - radius);
+            let push_back = (center + radius) - triangle_min;
+            if push_forward < 0.0 || push_back < 0.0 {
+                return None;
+            }
+
+            let (normal, depth) = if push_forward <= push_back {
+                (axis, push_forward)
+            } else {
+                (-axis, push_back)
+            };
+
+            let is_shallowest = match best {
+                Some(contact) => depth < contact.depth,
+                None => true,
+            };
+            if is_shallowest {
+                best = Some(Contact { normal, depth });
+            }
+        }
+
+        best
+    }
+}
@@ -14,9 +14,19 @@ pub enum PlayerProgress {
 }
 
 impl PlayerProgress {
+    // everyone who's finished is ahead of everyone still racing, who are
+    // ahead of anyone who hasn't started
+    fn stage(&self) -> u8 {
+        match self {
+            PlayerProgress::Finished { .. } => 0,
+            PlayerProgress::Racing { .. } => 1,
+            PlayerProgress::PreGame => 2,
+        }
+    }
+
+    // Orders players from first to last place: less is further ahead
     pub fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
-            (PlayerProgress::PreGame, PlayerProgress::PreGame) => Ordering::Equal,
             (
                 PlayerProgress::Racing {
                     lap_info: self_lap_info,
@@ -30,18 +40,76 @@ impl PlayerProgress {
                 } else if self_lap_info.zone != other_lap_info.zone {
                     self_lap_info.zone.cmp(&other_lap_info.zone).reverse()
                 } else {
-                    Ordering::Equal
+                    other_lap_info
+                        .lap_distance
+                        .partial_cmp(&self_lap_info.lap_distance)
+                        .unwrap_or(Ordering::Equal)
                 }
             }
-            (PlayerProgress::Finished { .. }, PlayerProgress::Racing { .. }) => Ordering::Less,
-            (PlayerProgress::Racing { .. }, PlayerProgress::Finished { .. }) => Ordering::Greater,
             (
                 PlayerProgress::Finished { finish_time },
                 PlayerProgress::Finished {
                     finish_time: other_finish_time,
                 },
             ) => finish_time.cmp(other_finish_time),
-            (_, _) => panic!("This comparison shouldn't be possible"),
+            (_, _) => self.stage().cmp(&other.stage()),
         }
     }
+
+    // How far along the track centerline we've gone over the whole race,
+    // given how long a lap is and how many of them there are
+    pub fn race_distance(&self, lap_length: f64, laps: LapNumber) -> f64 {
+        match self {
+            PlayerProgress::PreGame => 0.0,
+            PlayerProgress::Racing { lap_info } => {
+                lap_info.lap.saturating_sub(1) as f64 * lap_length + lap_info.lap_distance
+            }
+            PlayerProgress::Finished { .. } => laps as f64 * lap_length,
+        }
+    }
+}
+
+// Every lap a player's finished so far, and their splits on the one they're on
+#[derive(Clone, Default)]
+pub struct LapTimer {
+    // how far into the race the current lap started
+    lap_start: Duration,
+    splits: Vec<Duration>,
+    pub laps: Vec<LapTime>,
+}
+
+impl LapTimer {
+    pub fn starting_at(race_time: Duration) -> Self {
+        Self {
+            lap_start: race_time,
+            ..Default::default()
+        }
+    }
+
+    // we got into the next zone `race_time` into the race
+    pub fn split(&mut self, race_time: Duration) {
+        self.splits.push(race_time.saturating_sub(self.lap_start));
+    }
+
+    // we crossed the finish line at the end of `lap`, `race_time` into the
+    // race; the next lap starts right away
+    pub fn complete_lap(&mut self, lap: LapNumber, race_time: Duration) {
+        self.split(race_time);
+        self.laps.push(LapTime {
+            lap,
+            time: race_time.saturating_sub(self.lap_start),
+            splits: std::mem::take(&mut self.splits),
+        });
+        self.lap_start = race_time;
+    }
+
+    // the fastest of the laps before the last one, to compare it against
+    pub fn previous_best_lap(&self) -> Option<Duration> {
+        let earlier = self.laps.len().saturating_sub(1);
+        self.laps[..earlier].iter().map(|lap| lap.time).min()
+    }
+
+    pub fn best_lap(&self) -> Option<Duration> {
+        self.laps.iter().map(|lap| lap.time).min()
+    }
 }
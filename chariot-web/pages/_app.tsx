@@ -3,7 +3,7 @@ import '../styles/globals.css'
 import styles from '../styles/Defaults.module.scss';
 import type { AppProps } from 'next/app'
 import { VotingGameState, GlobalContext } from '../src/contexts/GlobalContext';
-import { Prompt, QuestionResult, Standing } from '../src/utils/networking';
+import { LeaderboardEntry, Prompt, QuestionResult, RacerPosition, Standing, TrackOutline } from '../src/utils/networking';
 import Logo from '../src/assets/Logo.png'
 import BG from '../src/assets/BG.png'
 import Image from 'next/image'
@@ -18,10 +18,16 @@ function MyApp({ Component, pageProps }: AppProps) {
 	const [prompt, setPrompt] = useState<Prompt | null>(null);
 	const [gameState, setGameState] = useState<VotingGameState>('waiting')
 	const [winner, setWinner] = useState<number | null>(null);
+	const [targets, setTargets] = useState<number[]>([]);
 	const [standings, setStandings] = useState<Standing[]>([]);
 	const [totalConnected, setTotalConnected] = useState(1);
 	const [countdownTime, setCountdownTime] = useState<Date | null>(null);
 	const [optionResults, setOptionResults] = useState<QuestionResult[]>([]);
+	const [trackOutline, setTrackOutline] = useState<TrackOutline | null>(null);
+	const [positions, setPositions] = useState<RacerPosition[]>([]);
+	const [predictionsOpen, setPredictionsOpen] = useState(false);
+	const [leaderboard, setLeaderboard] = useState<LeaderboardEntry[]>([]);
+	const [points, setPoints] = useState(0);
 
 	const funnyPhrases = ["I prefer folding",
 		"Hold onto your seats",
@@ -49,6 +55,8 @@ function MyApp({ Component, pageProps }: AppProps) {
 			setGameState,
 			winner,
 			setWinner,
+			targets,
+			setTargets,
 			standings,
 			setStandings,
 			totalConnected,
@@ -56,7 +64,17 @@ function MyApp({ Component, pageProps }: AppProps) {
 			countdownTime,
 			setCountdownTime,
 			optionResults,
-			setOptionResults
+			setOptionResults,
+			trackOutline,
+			setTrackOutline,
+			positions,
+			setPositions,
+			predictionsOpen,
+			setPredictionsOpen,
+			leaderboard,
+			setLeaderboard,
+			points,
+			setPoints
 		}}>
 			<div className={styles.backgroundImage} style={{ backgroundImage: `url(${BG.src})` }}>
 				<div className={styles.main}>
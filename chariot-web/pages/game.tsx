@@ -4,6 +4,11 @@ import { useContext, useEffect, useState } from "react";
 import { Button } from "../src/components/Button";
 import Grid from "../src/components/Grid/Grid";
 import Standings from "../src/components/Standings";
+import Leaderboard from "../src/components/Leaderboard";
+import Minimap from "../src/components/Minimap";
+import Predictions from "../src/components/Predictions";
+import Bets from "../src/components/Predictions/Bets";
+import Emotes from "../src/components/Emotes";
 import { GlobalContext } from "../src/contexts/GlobalContext";
 import { handleSocket, sendMessage } from "../src/utils/networking";
 import styles from './Game.module.scss';
@@ -17,7 +22,7 @@ const Game: NextPage = () => {
 	const context = useContext(GlobalContext);
 	const [selectedIdx, setSelectedIdx] = useState<number | null>(null);
 
-	const { socket, uuid, prompt, winner, totalConnected, countdownTime, gameState, optionResults } = context;
+	const { socket, uuid, prompt, winner, totalConnected, countdownTime, gameState, optionResults, predictionsOpen, standings } = context;
 	const othersConnected = totalConnected - 1;
 
 	useEffect(() => {
@@ -55,11 +60,12 @@ const Game: NextPage = () => {
 	}
 
 	const timeLeft = countdownTime ? countdownTime.getSeconds() - new Date().getSeconds() : -1;
+	const raceInProgress = !predictionsOpen && standings.some((standing) => standing.lap > 0);
 	const timeLeftText = gameState === 'voting' ? `Voting ends in ${timeLeft}s` : gameState === 'winner' ? `${timeLeft}s until effects subside` : `${timeLeft}s until next vote`
 
 	return (<div className={styles.container}>
 		<div className={styles.blockText}>
-			<p>{showStandings ? "Standings" : (timeLeft >= 0) ? timeLeftText : "Waiting for Next Vote"}</p>
+			<p>{showStandings ? "Standings" : predictionsOpen ? "Predict the podium!" : (timeLeft >= 0) ? timeLeftText : "Waiting for Next Vote"}</p>
 		</div>
 		{!showStandings && prompt !== null && gameState !== 'waiting' &&
 			<div className={styles.buttonLayout}>
@@ -77,8 +83,18 @@ const Game: NextPage = () => {
 				{prompt.options.length === 0 && <p>New Vote Coming Soon</p>}
 			</div>
 		}
+		{!showStandings && predictionsOpen &&
+			<Predictions />}
+		{!showStandings && raceInProgress &&
+			<Bets />}
+		{!showStandings && raceInProgress &&
+			<Emotes />}
+		{showStandings &&
+			<Minimap />}
 		{showStandings &&
 			<Standings />}
+		{showStandings &&
+			<Leaderboard />}
 
 		<div className={styles.standingsButton}>
 			<Button width="80%" text={showStandings ? "hide standings" : "see standings"} onClick={() => { setShowStandings(!showStandings) }} style='minimal' />
@@ -0,0 +1,41 @@
+import React, { useContext, useState } from 'react';
+import { GlobalContext } from '../../contexts/GlobalContext';
+import { Emote, sendMessage } from '../../utils/networking';
+import { Button } from '../Button';
+import styles from './Emotes.module.scss'
+
+const EMOTES: { emote: Emote, label: string }[] = [
+	{ emote: "Cheer", label: "yay!" },
+	{ emote: "Boo", label: "boo!" },
+	{ emote: "Laugh", label: "haha" },
+	{ emote: "Fire", label: "fire!" },
+];
+
+const Emotes: React.FC = () => {
+	const context = useContext(GlobalContext);
+	const { uuid, standings } = context;
+	const [target, setTarget] = useState<number | null>(null);
+
+	const racers = [...standings].sort((a, b) => Number(a.name) - Number(b.name));
+
+	return (
+		<div className={styles.container}>
+			<div className={styles.row}>
+				<Button text="all" width="20%" style='minimal' state={target === null ? 'selected' : 'unselected'} onClick={() => setTarget(null)} />
+				{racers.map((standing) => {
+					const id = Number(standing.name);
+					return (
+						<Button key={id} text={`P${id + 1}`} width="20%" style='minimal' state={target === id ? 'selected' : 'unselected'} onClick={() => setTarget(id)} />
+					)
+				})}
+			</div>
+			<div className={styles.row}>
+				{EMOTES.map(({ emote, label }) => (
+					<Button key={emote} text={label} width="25%" onClick={() => sendMessage(context, { Emote: [uuid, emote, target] })} />
+				))}
+			</div>
+		</div>
+	)
+}
+
+export default Emotes;
@@ -0,0 +1,25 @@
+import React, { useContext } from 'react';
+import { GlobalContext } from '../../contexts/GlobalContext';
+import styles from '../Standings/Standings.module.scss'
+
+const Leaderboard: React.FC = () => {
+	const { leaderboard } = useContext(GlobalContext);
+	return (
+		<table className={styles.table}>
+			<tr>
+				<th>rank</th>
+				<th>fan</th>
+				<th>points</th>
+			</tr>
+			{leaderboard.map((entry, idx) => (
+				<tr key={entry.name} className={entry.is_you ? styles.you : undefined}>
+					<td>{idx + 1}</td>
+					<td>{entry.is_you ? `${entry.name} (you)` : entry.name}</td>
+					<td>{entry.points}</td>
+				</tr>
+			))}
+		</table>
+	)
+}
+
+export default Leaderboard;
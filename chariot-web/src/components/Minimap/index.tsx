@@ -0,0 +1,52 @@
+import React, { useContext } from 'react';
+import { GlobalContext } from '../../contexts/GlobalContext';
+import { TrackRect } from '../../utils/networking';
+import styles from './Minimap.module.scss'
+
+const PLAYER_COLORS = ["#e6194b", "#4363d8", "#ffe119", "#3cb44b"];
+
+// the game draws its minimap with +x pointing down and +z pointing left, so we do too
+const toSvgRect = (rect: TrackRect) => ({
+	x: -rect.max_z,
+	y: rect.min_x,
+	width: rect.max_z - rect.min_z,
+	height: rect.max_x - rect.min_x,
+});
+
+const Minimap: React.FC = () => {
+	const { trackOutline, positions } = useContext(GlobalContext);
+
+	if (trackOutline === null) {
+		return <p>Waiting for the track to load...</p>;
+	}
+
+	const bounds = toSvgRect(trackOutline.bounds);
+	const racerSize = Math.max(bounds.width, bounds.height) / 40;
+
+	return (
+		<svg className={styles.minimap} viewBox={`${bounds.x} ${bounds.y} ${bounds.width} ${bounds.height}`}>
+			{trackOutline.zones.map((zone, idx) => (
+				<rect key={`zone${idx}`} className={styles.zone} {...toSvgRect(zone)} />
+			))}
+			{trackOutline.walls.map((wall, idx) => (
+				<rect key={`wall${idx}`} className={styles.wall} {...toSvgRect(wall)} />
+			))}
+			<rect className={styles.finishLine} {...toSvgRect(trackOutline.finish_line)} />
+			{positions.map((racer, idx) => {
+				const x = -racer.z;
+				const y = racer.x;
+				// heading is measured from +z towards +x
+				const headingX = -Math.cos(racer.heading) * racerSize * 2;
+				const headingY = Math.sin(racer.heading) * racerSize * 2;
+				return (
+					<g key={idx}>
+						<line x1={x} y1={y} x2={x + headingX} y2={y + headingY} stroke={PLAYER_COLORS[idx]} strokeWidth={racerSize / 2} />
+						<circle cx={x} cy={y} r={racerSize} fill={PLAYER_COLORS[idx]} />
+					</g>
+				)
+			})}
+		</svg>
+	)
+}
+
+export default Minimap;
@@ -0,0 +1,42 @@
+import React, { useContext, useState } from 'react';
+import { GlobalContext } from '../../contexts/GlobalContext';
+import { sendMessage } from '../../utils/networking';
+import { Button } from '../Button';
+import styles from './Predictions.module.scss'
+
+const WAGERS = [50, 100, 250];
+
+const Bets: React.FC = () => {
+	const context = useContext(GlobalContext);
+	const { uuid, standings, points } = context;
+	const [racer, setRacer] = useState<number | null>(null);
+
+	const racers = [...standings].sort((a, b) => Number(a.name) - Number(b.name));
+	const bet = (wager: number) => {
+		if (racer !== null && wager > 0 && wager <= points) {
+			sendMessage(context, { Bet: [uuid, racer, wager] });
+		}
+	}
+
+	return (
+		<div className={styles.container}>
+			<p>{points} points to bet. Who wins it all?</p>
+			<div className={styles.row}>
+				{racers.map((standing) => {
+					const id = Number(standing.name);
+					return (
+						<Button key={id} text={`P${id + 1}`} width="20%" state={racer === id ? 'selected' : 'unselected'} onClick={() => setRacer(id)} />
+					)
+				})}
+			</div>
+			<div className={styles.row}>
+				{WAGERS.map((wager) => (
+					<Button key={wager} text={`${wager}`} width="20%" clickable={racer !== null && wager <= points} onClick={() => bet(wager)} />
+				))}
+				<Button text="all in" width="20%" clickable={racer !== null && points > 0} onClick={() => bet(points)} />
+			</div>
+		</div>
+	)
+}
+
+export default Bets;
@@ -0,0 +1,48 @@
+import React, { useContext, useState } from 'react';
+import { GlobalContext } from '../../contexts/GlobalContext';
+import { sendMessage } from '../../utils/networking';
+import { Button } from '../Button';
+import styles from './Predictions.module.scss'
+
+const PLACES = ["1st", "2nd", "3rd"];
+
+const Predictions: React.FC = () => {
+	const context = useContext(GlobalContext);
+	const { uuid, standings } = context;
+	const [podium, setPodium] = useState<(number | null)[]>([null, null, null]);
+	const [submitted, setSubmitted] = useState(false);
+
+	const racers = [...standings].sort((a, b) => Number(a.name) - Number(b.name));
+	const complete = podium.every((racer) => racer !== null);
+
+	const pick = (place: number, racer: number) => {
+		// a racer can only be in one spot, so take them out of wherever they were
+		const newPodium = podium.map((r, idx) => idx === place ? racer : (r === racer ? null : r));
+		setPodium(newPodium);
+		setSubmitted(false);
+	}
+
+	return (
+		<div className={styles.container}>
+			{PLACES.map((placeName, place) => (
+				<div key={placeName} className={styles.row}>
+					<p>{placeName}</p>
+					{racers.map((racer) => {
+						const id = Number(racer.name);
+						return (
+							<Button key={id} text={`P${id + 1}`} width="20%" state={podium[place] === id ? 'selected' : 'unselected'} onClick={() => pick(place, id)} />
+						)
+					})}
+				</div>
+			))}
+			<Button width="100%" clickable={complete} state={submitted ? 'voted' : 'unselected'} text={submitted ? "prediction locked in!" : "lock in prediction"} onClick={() => {
+				if (complete) {
+					sendMessage(context, { Predict: [uuid, podium as [number, number, number]] });
+					setSubmitted(true);
+				}
+			}} />
+		</div>
+	)
+}
+
+export default Predictions;
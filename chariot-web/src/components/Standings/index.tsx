@@ -3,20 +3,22 @@ import { GlobalContext } from '../../contexts/GlobalContext';
 import styles from './Standings.module.scss'
 
 const Standings: React.FC = () => {
-	const { standings } = useContext(GlobalContext);
+	const { standings, targets, gameState } = useContext(GlobalContext);
 	return (
 		<table className={styles.table}>
 			<tr>
 				<th>player</th>
 				<th>rank</th>
 				<th>lap</th>
+				<th>progress</th>
 				<th>chair</th>
 			</tr>
 			{standings.sort(((a, b) => a.rank - b.rank)).map((standing) => (
-				<tr key={standing.name}>
+				<tr key={standing.name} className={gameState === 'winner' && targets.length < standings.length && targets.includes(Number(standing.name)) ? styles.targeted : undefined}>
 					<td>{standing.name}</td>
 					<td>{standing.rank}</td>
 					<td>{standing.lap}</td>
+					<td>{Math.round(standing.progress * 100)}%</td>
 					<td>{standing.chair}</td>
 				</tr>
 			))}
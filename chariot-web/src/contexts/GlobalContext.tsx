@@ -1,5 +1,5 @@
 import React from 'react';
-import { Prompt, QuestionResult, Standing } from '../utils/networking';
+import { LeaderboardEntry, Prompt, QuestionResult, RacerPosition, Standing, TrackOutline } from '../utils/networking';
 
 export type VotingGameState = 'voting' | 'winner' | 'waiting';
 
@@ -22,6 +22,9 @@ export type GlobalContextType = {
 	winner: number | null;
 	setWinner: React.Dispatch<React.SetStateAction<number | null>>;
 
+	targets: number[];
+	setTargets: React.Dispatch<React.SetStateAction<number[]>>;
+
 	optionResults: QuestionResult[];
 	setOptionResults: React.Dispatch<React.SetStateAction<QuestionResult[]>>;
 
@@ -34,6 +37,21 @@ export type GlobalContextType = {
 
 	countdownTime: Date | null;
 	setCountdownTime: React.Dispatch<React.SetStateAction<Date | null>>;
+
+	trackOutline: TrackOutline | null;
+	setTrackOutline: React.Dispatch<React.SetStateAction<TrackOutline | null>>;
+
+	positions: RacerPosition[];
+	setPositions: React.Dispatch<React.SetStateAction<RacerPosition[]>>;
+
+	predictionsOpen: boolean;
+	setPredictionsOpen: React.Dispatch<React.SetStateAction<boolean>>;
+
+	leaderboard: LeaderboardEntry[];
+	setLeaderboard: React.Dispatch<React.SetStateAction<LeaderboardEntry[]>>;
+
+	points: number;
+	setPoints: React.Dispatch<React.SetStateAction<number>>;
 };
 
 export const GlobalContext = React.createContext<GlobalContextType>(null as any);
\ No newline at end of file
@@ -1,8 +1,10 @@
 import { GlobalContextType } from "../contexts/GlobalContext"
 
+export type AudienceTarget = "Everyone" | "Leader" | "LastPlace" | { Player: number };
+
 export interface Prompt {
 	prompt: string;
-	options: { label: string, action: string }[]
+	options: { label: string, action: string, target: AudienceTarget }[]
 }
 
 export interface Standing {
@@ -10,6 +12,38 @@ export interface Standing {
 	chair: string,
 	rank: number,
 	lap: number,
+	progress: number, // how much of the race they've done, from 0 to 1
+}
+
+export type Emote = "Cheer" | "Boo" | "Laugh" | "Fire";
+
+export interface LeaderboardEntry {
+	name: string,
+	points: number,
+	is_you: boolean,
+}
+
+export interface RacerPosition {
+	x: number,
+	z: number,
+	heading: number, // radians, 0 faces +z
+	speed: number,
+	lap: number,
+	zone: number,
+}
+
+export interface TrackRect {
+	min_x: number,
+	max_x: number,
+	min_z: number,
+	max_z: number,
+}
+
+export interface TrackOutline {
+	bounds: TrackRect,
+	zones: TrackRect[],
+	walls: TrackRect[],
+	finish_line: TrackRect,
 }
 
 export interface QuestionResult {
@@ -25,7 +59,8 @@ export interface WSAudienceBoundMessage {
 	Winner?: {
 		choice: number,
 		vote_effect_time: number,
-		option_results: QuestionResult[]
+		option_results: QuestionResult[],
+		targets: number[] // The players the winning choice was applied to
 	}// The winning choice (tuple index)
 	Assignment?: string, // Sends a uuid that the server will use to identify the client
 	Standings?: [Standing], // state of the game
@@ -33,10 +68,20 @@ export interface WSAudienceBoundMessage {
 	Countdown?: {
 		time: number
 	} // the time left for something (state independent, used for new connections)
+	TrackOutline?: TrackOutline, // the shape of the track, sent once per race
+	Positions?: RacerPosition[], // where everyone is right now
+	PredictionsOpen?: boolean, // whether podium predictions are being accepted right now
+	Leaderboard?: {
+		entries: LeaderboardEntry[],
+		points: number // how many points we have left to bet
+	}
 }
 
 export interface WSServerBoundMessage {
 	Vote?: [string, number];
+	Predict?: [string, [number, number, number]]; // uuid, predicted 1st, 2nd and 3rd
+	Bet?: [string, number, number]; // uuid, racer to win, points to wager
+	Emote?: [string, Emote, number | null]; // uuid, reaction, racer it's aimed at (null for everyone)
 }
 
 export const handleSocket = (context: GlobalContextType, msg: MessageEvent) => {
@@ -48,6 +93,7 @@ export const handleSocket = (context: GlobalContextType, msg: MessageEvent) => {
 		context.setWinner(message.Winner.choice);
 		context.setCountdownTime(new Date(message.Winner.vote_effect_time));
 		context.setOptionResults(message.Winner.option_results);
+		context.setTargets(message.Winner.targets);
 		context.setGameState("winner");
 	} else if (message.Prompt !== undefined) {
 		context.setPrompt(message.Prompt.question);
@@ -55,10 +101,20 @@ export const handleSocket = (context: GlobalContextType, msg: MessageEvent) => {
 		context.setCountdownTime(new Date(message.Prompt.vote_close_time));
 		context.setGameState("voting");
 		context.setWinner(null);
+		context.setTargets([]);
 	} else if (message.Standings !== undefined) {
 		context.setStandings(message.Standings);
 	} else if (message.AudienceCount !== undefined) {
 		context.setTotalConnected(message.AudienceCount);
+	} else if (message.TrackOutline !== undefined) {
+		context.setTrackOutline(message.TrackOutline);
+	} else if (message.Positions !== undefined) {
+		context.setPositions(message.Positions);
+	} else if (message.PredictionsOpen !== undefined) {
+		context.setPredictionsOpen(message.PredictionsOpen);
+	} else if (message.Leaderboard !== undefined) {
+		context.setLeaderboard(message.Leaderboard.entries);
+		context.setPoints(message.Leaderboard.points);
 	} else if (message.Countdown !== undefined) {
 		context.setCountdownTime(new Date(message.Countdown.time));
 		context.setGameState('waiting');